@@ -1,6 +1,6 @@
-use bevy::{prelude::*, audio::{VolumeLevel, Volume}, asset::LoadState};
+use bevy::{prelude::*, audio::{VolumeLevel, Volume}, asset::HandleId};
 
-use crate::{hook::*, catch_stack::{FishLandedInStack, FishKnockedOutOfStack}, fish::FishLandedInWater};
+use crate::{catch_grade::CatchGrade, core::{ASSETS_LOADED, ResetLevel}, hook::*, catch_stack::{FishLandedInStack, FishKnockedOutOfStack}, fish::FishLandedInWater};
 
 pub struct HSLAudioPlugin;
 impl Plugin for HSLAudioPlugin {
@@ -10,25 +10,21 @@ impl Plugin for HSLAudioPlugin {
         .add_systems(PreStartup, (
             load_sounds,
         ))
-        .add_systems(Update, (
-            wait_for_audio_loaded,
-        ))
+        .add_systems(ASSETS_LOADED, add_music)
         .add_systems(PostUpdate,(
-            add_music,
             handle_reeling,
             handle_reeling_stop,
+            handle_reeling_stop_on_reset,
             handle_fish_landed,
             handle_fish_landed_in_water,
             handle_fish_knocked_out,
             handle_hook_landed,
+            handle_fish_escaped,
             handle_fish_reeled_to_surface
         ));
     }
 }
 
-#[derive(Event, Default)]
-pub struct MusicLoaded;
-
 #[derive(Resource, Default)]
 pub struct AudioHandles {
     bg_music: Option<Handle<AudioSource>>,
@@ -38,7 +34,20 @@ pub struct AudioHandles {
     critical_sound: Option<Handle<AudioSource>>,
     small_splash_sound: Option<Handle<AudioSource>>,
     splash_sound: Option<Handle<AudioSource>>,
-    loaded: bool,
+}
+
+impl AudioHandles {
+    pub fn ids(&self) -> Vec<HandleId> {
+        [
+            &self.bg_music,
+            &self.reeling_sound,
+            &self.landed_sound,
+            &self.knocked_out_sound,
+            &self.critical_sound,
+            &self.small_splash_sound,
+            &self.splash_sound,
+        ].into_iter().flatten().map(|handle| handle.id()).collect()
+    }
 }
 
 #[derive(Component)]
@@ -48,18 +57,14 @@ pub struct Music;
 pub struct ReelingSound;
 
 fn add_music(
-    mut on_load: EventReader<MusicLoaded>,
     audio_handles: Res<AudioHandles>,
     mut commands: Commands
 ) {
-    if !on_load.is_empty() {
-        on_load.clear();
-        commands.spawn(AudioBundle {
-            source: audio_handles.bg_music.as_ref().expect("Music should be loaded").clone(),
-            settings: PlaybackSettings::LOOP,
-            ..default()
-        });
-    }
+    commands.spawn(AudioBundle {
+        source: audio_handles.bg_music.as_ref().expect("Music should be loaded").clone(),
+        settings: PlaybackSettings::LOOP,
+        ..default()
+    });
 }
 
 fn load_sounds(
@@ -75,34 +80,13 @@ fn load_sounds(
     audio_handles.small_splash_sound = Some(asset_server.load("small_splash.wav"));
 }
 
-fn wait_for_audio_loaded(
-    mut audio_handles: ResMut<AudioHandles>,
-    asset_server: Res<AssetServer>,
-    mut on_load_complete: EventWriter<MusicLoaded>
-) {
-    if !audio_handles.loaded {
-        let load_state = 
-            asset_server.get_load_state(audio_handles.bg_music
-                .as_ref().expect("audio should be loaded").clone());
-        if load_state == LoadState::Loaded {
-            on_load_complete.send_default();
-            audio_handles.loaded = true;
-        }
-    }
-}
-
 fn handle_reeling(
     mut on_catch: EventReader<FishCaught>,
     audio: Res<AudioHandles>,
     mut commands: Commands
 ) {
-    let mut is_critical = false;
-    let has_events = !on_catch.is_empty();
-    for event in on_catch.iter() {
-        is_critical |= event.is_critical;
-    }
-    if has_events {
-        if is_critical {
+    if let Some(grade) = on_catch.iter().map(|event| event.grade).last() {
+        if grade.is_critical() {
             commands.spawn((
                 AudioBundle {
                     source: audio.critical_sound.as_ref().expect("Audio should be loaded").clone(),
@@ -120,22 +104,50 @@ fn handle_reeling(
                 },
                 ReelingSound
             ));
+            //a lower, quieter take on the critical sting for the catches that were close
+            let stinger = match grade {
+                CatchGrade::Great => Some((0.8, 0.6)),
+                CatchGrade::Good => Some((0.6, 0.35)),
+                _ => None,
+            };
+            if let Some((speed, volume)) = stinger {
+                commands.spawn(AudioBundle {
+                    source: audio.critical_sound.as_ref().expect("Audio should be loaded").clone(),
+                    settings: PlaybackSettings::DESPAWN
+                        .with_speed(speed)
+                        .with_volume(Volume::Relative(VolumeLevel::new(volume)))
+                });
+            }
         }
     }
 }
 
 fn handle_reeling_stop(
     mut on_catch: EventReader<ReeledToSurface>,
+    mut on_snapped: EventReader<LineSnapped>,
     mut audio: Query<&mut AudioSink, With<ReelingSound>>,
 ) {
-    if !on_catch.is_empty() {
+    if !on_catch.is_empty() || !on_snapped.is_empty() {
         on_catch.clear();
+        on_snapped.clear();
         if let Ok(audio) = audio.get_single_mut() {
             audio.stop();
         }
     }
 }
 
+fn handle_reeling_stop_on_reset(
+    mut on_reset: EventReader<ResetLevel>,
+    audio: Query<&AudioSink, With<ReelingSound>>,
+) {
+    if !on_reset.is_empty() {
+        on_reset.clear();
+        for audio in &audio {
+            audio.stop();
+        }
+    }
+}
+
 fn handle_fish_landed(
     mut on_land: EventReader<FishLandedInStack>,
     audio: Res<AudioHandles>,
@@ -181,6 +193,20 @@ fn handle_hook_landed(
     }
 }
 
+fn handle_fish_escaped(
+    mut on_escape: EventReader<FishEscaped>,
+    audio: Res<AudioHandles>,
+    mut commands: Commands
+) {
+    if !on_escape.is_empty() {
+        on_escape.clear();
+        commands.spawn(AudioBundle {
+            source: audio.small_splash_sound.as_ref().expect("Audio should be loaded").clone(),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::Relative(VolumeLevel::new(0.6)))
+        });
+    }
+}
+
 fn handle_fish_reeled_to_surface(
     mut on_reeled: EventReader<ReeledToSurface>,
     audio: Res<AudioHandles>,
@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 use crate::{
+    catch_grade::CatchGrade,
     core::*, 
     constants::*,
     hook::*,
     catch_stack::*, 
-    fish:: *
+    fish:: *,
+    tuning::Tuning
 };
 
 pub struct BearPlugin;
@@ -19,14 +21,16 @@ impl Plugin for BearPlugin {
             update_critical_anim,
             animate_bear,
             update_fishing_line,  
-        ))
+        ).run_if(in_level))
         .add_systems(PostUpdate, (
-            draw_fishing_line,
+            draw_fishing_line.run_if(in_level),
             handle_bear_on_reeled_to_surface,
+            handle_bear_on_line_snapped,
             handle_bear_on_cast,
             handle_bear_on_catch,
             handle_bear_on_fish_landed,
             handle_bear_on_hooked,
+            handle_bear_on_escape,
             handle_bear_on_reset,
         ));
     }
@@ -82,13 +86,19 @@ impl BearAnimations {
         }
     }
 
-    fn reeling() -> BearAnimations {
+    //the quicker the catch, the harder the bear pulls
+    fn reeling(grade: CatchGrade) -> BearAnimations {
+        let (rate_multiplier, stretch_y) = match grade {
+            CatchGrade::Perfect | CatchGrade::Great => (0.5, 0.05),
+            CatchGrade::Good => (0.5, 0.02),
+            CatchGrade::Late => (0.3, 0.01),
+        };
         BearAnimations {
             state: BearAnimationStates::Reeling,
             timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-            rate_multiplier: 0.5,
+            rate_multiplier,
             stretch_x: 0.0,
-            stretch_y: 0.02,
+            stretch_y,
         }
     }
 
@@ -102,13 +112,16 @@ impl BearAnimations {
         }
     }
 
-    fn casting() -> BearAnimations {
+    //farther casts get a bigger lunge
+    fn casting(target: Vec2) -> BearAnimations {
+        let reach = ((target.x - CAST_NEAR_X) / (CAST_FAR_X - CAST_NEAR_X)).clamp(0.0, 1.0);
+        let stretch = 0.15 + 0.35 * reach;
         BearAnimations { 
             state: BearAnimationStates::Casting, 
             timer: Timer::from_seconds(0.3, TimerMode::Once),
             rate_multiplier: 0.5,
-            stretch_x: 0.3,
-            stretch_y: -0.3,
+            stretch_x: stretch,
+            stretch_y: -stretch,
         }
     }
 
@@ -244,13 +257,31 @@ fn update_critical_anim(
     }
 }
 
+fn handle_bear_on_line_snapped(
+    mut on_snapped: EventReader<LineSnapped>,
+    mut bear_query: Query<(&mut TextureAtlasSprite, &mut BearAnimations), With<Bear>>
+) {
+    if !on_snapped.is_empty() {
+        on_snapped.clear();
+        let (mut bear_sprite, mut animation) = bear_query.single_mut();
+        *animation = BearAnimations::waiting();
+        bear_sprite.index = BearSpriteStates::Casting.into();
+    }
+}
+
 fn handle_bear_on_reeled_to_surface(
     mut on_reeled: EventReader<ReeledToSurface>,
-    mut bear_query: Query<(&mut TextureAtlasSprite, &mut BearAnimations), With<Bear>>
+    mut bear_query: Query<(&mut TextureAtlasSprite, &mut BearAnimations), With<Bear>>,
+    stack_query: Query<(&Transform, &CatchStack)>,
+    target: Res<TargetStack>
 ) {
     if !on_reeled.is_empty() {
         on_reeled.clear();
         let (mut bear_sprite, mut animation) = bear_query.single_mut();
+        //the bear throws over its shoulder, so it turns around for a stack on its right
+        if let Some((stack_pos, _)) = stack_query.iter().find(|(_, stack)| stack.index == target.index) {
+            bear_sprite.flip_x = stack_pos.translation.x > BEAR_POS.x;
+        }
         let bear_state: BearSpriteStates = bear_sprite.index.into();
         if bear_state != BearSpriteStates::Critical1 
             && bear_state != BearSpriteStates::Critical2 
@@ -266,11 +297,10 @@ fn handle_bear_on_cast(
     mut bear_query: Query<(Entity, &mut TextureAtlasSprite, &mut BearAnimations), With<Bear>>,
     mut commands: Commands
 ){
-    if !on_cast.is_empty() {
-        on_cast.clear();
+    if let Some(event) = on_cast.iter().last() {
         let (bear_entity, mut bear_sprite, mut animations) = bear_query.single_mut();
         bear_sprite.index = BearSpriteStates::Fishing.into();
-        *animations = BearAnimations::casting();
+        *animations = BearAnimations::casting(event.target);
         commands.entity(bear_entity).remove::<BearCriticalFlash>();
     }
 }
@@ -288,6 +318,7 @@ fn handle_bear_on_fish_landed(
             *animation = BearAnimations::waiting();
             bear_sprite.index = BearSpriteStates::Casting.into();
         }
+        bear_sprite.flip_x = false;
         commands.entity(bear_entity).remove::<BearCriticalFlash>();
     }
 }
@@ -303,6 +334,17 @@ fn handle_bear_on_hooked(
     }
 }
 
+fn handle_bear_on_escape(
+    mut on_escape: EventReader<FishEscaped>,
+    mut bear_query: Query<&mut BearAnimations, With<Bear>>
+) {
+    if !on_escape.is_empty() {
+        on_escape.clear();
+        let mut animation = bear_query.single_mut();
+        *animation = BearAnimations::fishing();
+    }
+}
+
 fn handle_bear_on_catch(
     mut on_reel: EventReader<FishCaught>,
     mut bear_query: Query<(Entity, &mut TextureAtlasSprite, &mut BearAnimations), With<Bear>>,
@@ -310,14 +352,14 @@ fn handle_bear_on_catch(
 ) {
     for event in on_reel.iter() {
         let (bear_entity, mut bear_sprite, mut animation) = bear_query.single_mut();
-        if event.is_critical {
+        if event.grade.is_critical() {
             *animation = BearAnimations::dancing();
             bear_sprite.index = BearSpriteStates::Critical1.into();
             commands.entity(bear_entity).insert(BearCriticalFlash {
                 anim_timer: Timer::from_seconds(0.1, TimerMode::Repeating)
             });
         } else {
-            *animation = BearAnimations::reeling();
+            *animation = BearAnimations::reeling(event.grade);
             bear_sprite.index = BearSpriteStates::Reeling.into();
         }
     }
@@ -364,9 +406,10 @@ fn update_fishing_line(
 ) {
     if let Ok(bear_sprite) = bear_query.get_single() {
         if let Ok(mut line_start_pos) = line_start_query.get_single_mut() {
+            let facing = if bear_sprite.flip_x { -1.0 } else { 1.0 };
             line_start_pos.translation = 
                 Vec3::new(
-                    SPRITE_LINE_STARTS[bear_sprite.index].x,
+                    SPRITE_LINE_STARTS[bear_sprite.index].x * facing,
                     SPRITE_LINE_STARTS[bear_sprite.index].y + HALF_BEAR_HEIGHT,
                     0.0);
         }
@@ -374,65 +417,75 @@ fn update_fishing_line(
 }
 
 
+//the line stays gray until the tension gets close to snapping, then reddens the rest of the way
+fn line_tension_color(tension: f32, warning: f32) -> Color {
+    let strain = ((tension - warning) / (1.0 - warning)).clamp(0.0, 1.0);
+    let gray = Vec4::from(Color::GRAY);
+    let red = Vec4::from(Color::RED);
+    Color::from(gray.lerp(red, strain))
+}
+
 fn draw_fishing_line(
     hook_query: Query<(&Transform, Option<&WaitingToBeCast>, Option<&HookInWater>), With<Hook>>,
     fish_query: Query<(&Children, Option<&Reeling>, Option<&Hooked>), With<Fish>>,
     mouth_query: Query<(Entity, &GlobalTransform), With<FishMouth>>,
-    popup_query: Query<(), With<PopupTimer>>,
     line_start_query: Query<&GlobalTransform, With<LineStartPoint>>,
+    tuning: Res<Tuning>,
     mut gizmos: Gizmos
 ) {
-    const LINE_COLOR: Color = Color::GRAY;
-    if popup_query.is_empty() {
-        if let Ok(line_start_pos) = line_start_query.get_single() {
-            let line_start_pos = line_start_pos.translation();
-            if let Ok((hook_pos, is_waiting, is_in_water)) = hook_query.get_single() {
-                const HOOK_OFFSET: Vec3 = Vec3::new(0.0, 25.0, 0.0);
-                let mut line_target: Option<Vec3> = None;
-                for (fish_children, is_reeling, is_hooked) in &fish_query {
-                    if is_reeling.is_some() || is_hooked.is_some() {
-                        for (mouth_entity, mouth_pos) in &mouth_query {
-                            if mouth_entity == fish_children[0] {
-                                line_target = Some(mouth_pos.translation());
-                                break;
-                            }
+    let mut line_color = Color::GRAY;
+    if let Ok(line_start_pos) = line_start_query.get_single() {
+        let line_start_pos = line_start_pos.translation();
+        if let Ok((hook_pos, is_waiting, in_water)) = hook_query.get_single() {
+            const HOOK_OFFSET: Vec3 = Vec3::new(0.0, 25.0, 0.0);
+            let mut line_target: Option<Vec3> = None;
+            for (fish_children, is_reeling, is_hooked) in &fish_query {
+                if let Some(reeling) = is_reeling {
+                    line_color = line_tension_color(reeling.tension, tuning.reeling.tension_warning);
+                }
+                if is_reeling.is_some() || is_hooked.is_some() {
+                    for (mouth_entity, mouth_pos) in &mouth_query {
+                        if mouth_entity == fish_children[0] {
+                            line_target = Some(mouth_pos.translation());
+                            break;
                         }
                     }
                 }
-                let line_target = line_target.unwrap_or(hook_pos.translation + HOOK_OFFSET);
-                match (is_waiting.is_some(), is_in_water.is_some()) {
-                    (true, _) =>
-                        gizmos.line(line_start_pos, line_target, LINE_COLOR),
-                    (_, true) => {        
-                        let visual_surface_y = WATER_POS.y + WATER_SIZE.y / 2.0 - 80.0;
-                        let distance_to_hook_x = line_start_pos.x - CAST_TARGET_POS.x;
-                        let distance_to_surface_y = line_start_pos.y - visual_surface_y;
-                        
-                        let node_near_pole = Vec3::new(
-                            CAST_TARGET_POS.x + 0.9 * distance_to_hook_x, 
-                            visual_surface_y + 0.3 * distance_to_surface_y,
-                            0.0
-                        );
-                        let node_near_surface = Vec3::new(
-                            CAST_TARGET_POS.x + 0.4 * distance_to_hook_x, 
-                            visual_surface_y + 0.1 * distance_to_surface_y,
-                            0.0
-                        );
-                        let node_at_surface = Vec3::new(CAST_TARGET_POS.x, visual_surface_y, 0.0);
-                        let points = [[
-                            line_start_pos, 
-                            node_near_pole,
-                            node_near_surface,
-                            node_at_surface,
-                            ]];
-                        let bezier = Bezier::new(points);
-                        gizmos.linestrip(bezier.to_curve().iter_positions(50), LINE_COLOR);
-                        gizmos.line(node_at_surface, Vec3::new(line_target.x, line_target.y, 0.0), LINE_COLOR);
-                    },
-                    (false, false) => 
-                        gizmos.line(line_start_pos, line_target, LINE_COLOR)
-                };
             }
+            let line_target = line_target.unwrap_or(hook_pos.translation + HOOK_OFFSET);
+            match (is_waiting.is_some(), in_water) {
+                (true, _) =>
+                    gizmos.line(line_start_pos, line_target, line_color),
+                (_, Some(in_water)) => {        
+                    let surface_x = in_water.surface_x;
+                    let visual_surface_y = WATER_POS.y + WATER_SIZE.y / 2.0 - 80.0;
+                    let distance_to_hook_x = line_start_pos.x - surface_x;
+                    let distance_to_surface_y = line_start_pos.y - visual_surface_y;
+                    
+                    let node_near_pole = Vec3::new(
+                        surface_x + 0.9 * distance_to_hook_x, 
+                        visual_surface_y + 0.3 * distance_to_surface_y,
+                        0.0
+                    );
+                    let node_near_surface = Vec3::new(
+                        surface_x + 0.4 * distance_to_hook_x, 
+                        visual_surface_y + 0.1 * distance_to_surface_y,
+                        0.0
+                    );
+                    let node_at_surface = Vec3::new(surface_x, visual_surface_y, 0.0);
+                    let points = [[
+                        line_start_pos, 
+                        node_near_pole,
+                        node_near_surface,
+                        node_at_surface,
+                        ]];
+                    let bezier = Bezier::new(points);
+                    gizmos.linestrip(bezier.to_curve().iter_positions(50), line_color);
+                    gizmos.line(node_at_surface, Vec3::new(line_target.x, line_target.y, 0.0), line_color);
+                },
+                (false, None) => 
+                    gizmos.line(line_start_pos, line_target, line_color)
+            };
         }
     }
 }
@@ -465,6 +518,7 @@ fn handle_bear_on_reset(
             commands.entity(entity).remove::<BearCriticalFlash>();
             *animation = BearAnimations::waiting();
             sprite.index = BearSpriteStates::Casting.into();
+            sprite.flip_x = false;
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,157 @@
+use bevy::{prelude::*, sprite::Anchor};
+use serde::Deserialize;
+use crate::core::{in_level, FontHandles, ResetLevel};
+use crate::hook::{FishCaught, Hook};
+
+pub struct CatchGradePlugin;
+impl Plugin for CatchGradePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, float_grade_popups.run_if(in_level))
+        .add_systems(PostUpdate, (
+            show_grade_popup,
+            clear_grade_popups_on_reset,
+        ));
+    }
+}
+
+/// How quickly the hook was set after the bite, best first.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CatchGrade {
+    /// Inside the level's `critical_time`, the fish skips reeling and flies straight to the stack.
+    Perfect,
+    Great,
+    Good,
+    Late,
+}
+
+impl CatchGrade {
+    pub fn from_reaction_time(react_time_s: f32, critical_time_s: f32, windows: &CatchWindows) -> CatchGrade {
+        if react_time_s < critical_time_s {
+            CatchGrade::Perfect
+        } else if react_time_s < windows.great_s {
+            CatchGrade::Great
+        } else if react_time_s < windows.good_s {
+            CatchGrade::Good
+        } else {
+            CatchGrade::Late
+        }
+    }
+
+    pub fn is_critical(&self) -> bool {
+        *self == CatchGrade::Perfect
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CatchGrade::Perfect => "PERFECT!",
+            CatchGrade::Great => "GREAT",
+            CatchGrade::Good => "GOOD",
+            CatchGrade::Late => "LATE",
+        }
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        match self {
+            CatchGrade::Perfect => 3.0,
+            CatchGrade::Great => 2.0,
+            CatchGrade::Good => 1.5,
+            CatchGrade::Late => 1.0,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            CatchGrade::Perfect => Color::GOLD,
+            CatchGrade::Great => Color::GREEN,
+            CatchGrade::Good => Color::WHITE,
+            CatchGrade::Late => Color::GRAY,
+        }
+    }
+}
+
+/// Seconds after the bite that each grade ends, from the tuning file. Perfect
+/// is per level, see `LevelDefinition::critical_time`.
+#[derive(Deserialize, Clone, Debug)]
+pub struct CatchWindows {
+    pub great_s: f32,
+    pub good_s: f32,
+}
+
+impl CatchWindows {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.great_s <= 0.0 || self.good_s < self.great_s {
+            errors.push(format!("catch_windows must be positive and in order, got {:?}", self));
+        }
+        errors
+    }
+}
+
+#[derive(Component)]
+struct GradePopup {
+    timer: Timer
+}
+
+const POPUP_RISE: f32 = 150.0;
+
+fn show_grade_popup(
+    mut on_catch: EventReader<FishCaught>,
+    hook_query: Query<&GlobalTransform, With<Hook>>,
+    fonts: Res<FontHandles>,
+    mut commands: Commands
+) {
+    for event in on_catch.iter() {
+        let Ok(hook_pos) = hook_query.get(event.hook_entity) else {
+            continue;
+        };
+        let grade = event.grade;
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("{} x{}", grade.label(), grade.score_multiplier()),
+                    TextStyle {
+                        font: fonts.timer_font_handle.as_ref().expect("Fonts should be loaded").clone(),
+                        font_size: 90.0,
+                        color: grade.color(),
+                    }),
+                text_anchor: Anchor::BottomCenter,
+                transform: Transform::from_translation(hook_pos.translation().truncate().extend(60.0)),
+                ..default()
+            },
+            GradePopup {
+                timer: Timer::from_seconds(0.8, TimerMode::Once)
+            }
+        ));
+    }
+}
+
+//rises while fading out, then goes away
+fn float_grade_popups(
+    mut popup_query: Query<(Entity, &mut Transform, &mut Text, &mut GradePopup)>,
+    time: Res<Time>,
+    mut commands: Commands
+) {
+    for (entity, mut transform, mut text, mut popup) in &mut popup_query {
+        popup.timer.tick(time.delta());
+        if popup.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += POPUP_RISE / popup.timer.duration().as_secs_f32() * time.delta_seconds();
+        text.sections[0].style.color.set_a(popup.timer.percent_left());
+    }
+}
+
+fn clear_grade_popups_on_reset(
+    mut on_reset: EventReader<ResetLevel>,
+    popup_query: Query<Entity, With<GradePopup>>,
+    mut commands: Commands
+) {
+    if !on_reset.is_empty() {
+        on_reset.clear();
+        for entity in &popup_query {
+            commands.entity(entity).despawn();
+        }
+    }
+}
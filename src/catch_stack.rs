@@ -1,9 +1,17 @@
 use bevy::prelude::*;
+use serde::Deserialize;
+use bevy::sprite::Anchor;
 use crate::constants::*;
+use crate::controls::Action;
 use crate::core::*;
 use crate::fish::*;
 use crate::hook::*;
+use crate::level::CurrentLevel;
 use crate::physics::*;
+use crate::settings::Settings;
+use crate::species::FishSpeciesList;
+use crate::stack_rules::StackRules;
+use crate::tuning::Tuning;
 
 pub struct CatchStackPlugin;
 impl Plugin for CatchStackPlugin {
@@ -12,14 +20,22 @@ impl Plugin for CatchStackPlugin {
         .add_event::<FishLandedInStack>()
         .add_event::<FishKnockedOutOfStack>()
         .add_event::<StackCompleted>()
+        .insert_resource(TargetStack::default())
         .add_systems(Startup, 
-            add_catch_stack)
+            add_catch_stacks)
+        .add_systems(ASSETS_LOADED, add_target_marker)
         .add_systems(Update, (
             interpolate_flying_arc,
-            update_falling_fish
-        ))
+            update_falling_fish,
+            wobble_stack,
+            update_target_marker.run_if(
+                resource_changed::<TargetStack>()
+                .or_else(resource_changed::<Settings>())
+                .or_else(on_event::<ResetLevel>()))
+        ).run_if(in_level))
+        .add_systems(Update, choose_target_stack.run_if(in_state(GameState::Playing)))
         .add_systems(PostUpdate,(
-            reset_stack,
+            reset_stacks,
             handle_fish_reeled_to_surface,
             handle_fish_knocked_out_of_stack,
             handle_fish_landed_in_stack,
@@ -35,20 +51,79 @@ pub struct FlyingToStack {
     pub start_pos: Vec2,
     pub end_pos: Vec2,
     pub start_time_s: f32,
-    pub end_time_s: f32
+    pub end_time_s: f32,
+    pub stack_entity: Entity
 }
 
 
-#[derive(Component, Default)]
+/// One of the level's stacks, see `LevelDefinition::stacks`.
+#[derive(Component)]
 pub struct CatchStack {
-    pub total_fish: usize,
-    pub fish: [Option<StackedFish>; FISH_PER_LEVEL]
+    /// Where it is in `LevelDefinition::stacks`.
+    pub index: usize,
+    pub rule: StackRules,
+    /// Fish currently on the stack, bottom first.
+    pub fish: Vec<StackedFish>,
+    /// How far the stack leans in radians, positive leans right.
+    pub sway: f32,
+    pub sway_vel: f32
+}
+
+impl CatchStack {
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.fish.iter().map(|fish| fish.entity)
+    }
+
+    //how far the stack has moved sideways at `height` above its base
+    fn lean_x(&self, height: f32) -> f32 {
+        height * self.sway.sin()
+    }
 }
 
+/// Which stack the next fish reeled to the surface flies to.
+#[derive(Resource, Default)]
+pub struct TargetStack {
+    pub index: usize
+}
+
+#[derive(Component)]
+struct TargetStackMarker;
+
+type TargetMarkerFilter = (With<TargetStackMarker>, Without<CatchStack>);
+
 #[derive(Copy, Clone)]
 pub struct StackedFish {
     entity: Entity,
-    fish_size: usize
+    fish_size: usize,
+    stack_height: f32,
+    contact_width: f32
+}
+
+/// How the stack sways when fish land on it, from the tuning file.
+#[derive(Deserialize, Clone, Debug)]
+pub struct StackWobble {
+    /// Pulls the stack back upright.
+    pub stiffness: f32,
+    pub damping: f32,
+    /// How much the landing fish's sideways speed tips the stack.
+    pub landing_push: f32,
+    /// How much landing off the top of the stack tips it.
+    pub offset_push: f32,
+    /// Leaning further than this in radians throws off the top fish.
+    pub topple_angle: f32,
+}
+
+impl StackWobble {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.stiffness <= 0.0 || self.damping < 0.0 {
+            errors.push(format!("stack_wobble needs a positive stiffness and damping, got {:?}", self));
+        }
+        if self.topple_angle <= 0.0 {
+            errors.push(format!("stack_wobble.topple_angle must be positive, got {}", self.topple_angle));
+        }
+        errors
+    }
 }
 
 #[derive(Component)]
@@ -58,8 +133,13 @@ pub struct InCatchStack;
 pub struct FishLandedInStack {
     pub entity: Entity,
     pub fish_size: usize,
+    pub stack_height: f32,
+    /// Width of the fish where it rests on the stack, see `contact_width`.
+    pub contact_width: f32,
     pub position: Vec2,
-    pub return_lane_y: f32
+    pub velocity: Vec2,
+    pub return_lane_y: f32,
+    pub stack_entity: Entity
 }
 
 #[derive(Component)]
@@ -71,55 +151,127 @@ pub struct FallingInStack {
 #[derive(Event, Default)]
 pub struct StackCompleted;
 
-fn add_catch_stack(
+fn spawn_catch_stacks(current_level: &CurrentLevel, commands: &mut Commands) {
+    for (index, stack) in current_level.definition().stacks.iter().enumerate() {
+        commands.spawn((
+            Transform::from_translation(stack.position.extend(STACK_POS.z)),
+            CatchStack {
+                index,
+                rule: stack.rule,
+                fish: Vec::new(),
+                sway: 0.0,
+                sway_vel: 0.0
+            }
+        ));
+    }
+}
+
+fn add_catch_stacks(
+    current_level: Res<CurrentLevel>,
     mut commands: Commands
 ){
-    commands.spawn(
-        (
-        Transform::from_translation(STACK_POS),
-        CatchStack::default())
-    );
+    spawn_catch_stacks(&current_level, &mut commands);
 }
 
-fn reset_stack(
+//the next level may want its stacks somewhere else, so they're all rebuilt
+fn reset_stacks(
     mut completed_events: EventReader<ResetLevel>,
-    mut stack_query: Query<&mut CatchStack>,
+    stack_query: Query<Entity, With<CatchStack>>,
+    current_level: Res<CurrentLevel>,
+    mut target: ResMut<TargetStack>,
+    mut commands: Commands
 ) {
     if !completed_events.is_empty() {
         completed_events.clear();
-        let mut catch_stack = stack_query.single_mut();
-        catch_stack.total_fish = 0;
-        for item in catch_stack.fish.iter_mut() {
-            *item = None;
+        for entity in &stack_query {
+            commands.entity(entity).despawn();
         }
+        spawn_catch_stacks(&current_level, &mut commands);
+        target.index = 0;
+    }
+}
+
+fn choose_target_stack(
+    actions: Res<Input<Action>>,
+    current_level: Res<CurrentLevel>,
+    mut target: ResMut<TargetStack>
+) {
+    let stack_count = current_level.definition().stacks.len();
+    if stack_count > 1 && actions.just_pressed(Action::SwitchStack) {
+        target.index = (target.index + 1) % stack_count;
+    }
+}
+
+fn add_target_marker(
+    fonts: Res<FontHandles>,
+    mut commands: Commands
+) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", TextStyle {
+                font: fonts.timer_font_handle.as_ref().expect("Fonts should be loaded").clone(),
+                font_size: 50.0,
+                color: Color::BLACK,
+            }),
+            text_anchor: Anchor::TopCenter,
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        TargetStackMarker
+    ));
+}
+
+//only shown when there's a choice to make
+fn update_target_marker(
+    target: Res<TargetStack>,
+    settings: Res<Settings>,
+    stack_query: Query<(&Transform, &CatchStack)>,
+    mut marker_query: Query<(&mut Transform, &mut Text, &mut Visibility), TargetMarkerFilter>
+) {
+    let Ok((mut marker_pos, mut text, mut visibility)) = marker_query.get_single_mut() else {
+        return;
+    };
+    let target_pos = stack_query.iter().find(|(_, stack)| stack.index == target.index);
+    match target_pos {
+        Some((stack_pos, _)) if stack_query.iter().count() > 1 => {
+            marker_pos.translation = stack_pos.translation.truncate().extend(50.0) - Vec3::new(0.0, 60.0, 0.0);
+            text.sections[0].value = format!("TARGET ({})", settings.bindings.describe_keys(Action::SwitchStack));
+            *visibility = Visibility::Inherited;
+        },
+        _ => *visibility = Visibility::Hidden,
     }
 }
 
 fn handle_fish_reeled_to_surface(
     mut on_reeled: EventReader<ReeledToSurface>,
-    catch_stack: Query<(&Transform, &CatchStack)>,
+    catch_stack: Query<(Entity, &Transform, &CatchStack)>,
     fish_query: Query<(Entity, &Transform), With<Fish>>,
-    time: Res<Time>,
+    (time, tuning, target): (Res<Time>, Res<Tuning>, Res<TargetStack>),
     mut commands: Commands,
 ) {
-    let (catch_stack_pos, catch_stack) = catch_stack.single();
+    let Some((stack_entity, catch_stack_pos, catch_stack)) = catch_stack.iter()
+        .find(|(_, _, stack)| stack.index == target.index) else {
+        return;
+    };
     for event in on_reeled.iter() {
         for (fish_entity, fish_pos) in &fish_query {
             if fish_entity == event.entity {
                 let catch_stack_pos = catch_stack_pos.translation;
-                let target_y = catch_stack_pos.y + calculate_stack_height(&catch_stack.fish);
+                let height = calculate_stack_height(&catch_stack.fish);
+                let target_y = catch_stack_pos.y + height;
+                //aims for where the top is now, the stack may have swayed away by the time it lands
                 let catch_stack_pos = Vec3::new(
-                    catch_stack_pos.x, 
+                    catch_stack_pos.x + catch_stack.lean_x(height), 
                     target_y,
                     catch_stack_pos.z);
                 commands.entity(event.entity).remove::<Reeling>();
-                send_fish_to_stack(fish_pos.translation, catch_stack_pos, GRAVITY, time.elapsed_seconds(), &mut commands, event.entity);
+                send_fish_to_stack(fish_pos.translation, catch_stack_pos, stack_entity, tuning.gravity, time.elapsed_seconds(), &mut commands, event.entity);
             }
         }
     }
 }
 
-fn send_fish_to_stack(fish_pos: Vec3, catch_stack_pos: Vec3, gravity: f32, elapsed_time: f32, commands: &mut Commands, entity: Entity) {
+fn send_fish_to_stack(fish_pos: Vec3, catch_stack_pos: Vec3, stack_entity: Entity, gravity: f32, elapsed_time: f32, commands: &mut Commands, entity: Entity) {
     let (arc_vel, arc_time) = calculate_time_and_initial_vel_for_arc(fish_pos.x, fish_pos.y, catch_stack_pos.x, catch_stack_pos.y, gravity, 900.0);
     let flying = FlyingToStack {
         start_vel: arc_vel,
@@ -128,6 +280,7 @@ fn send_fish_to_stack(fish_pos: Vec3, catch_stack_pos: Vec3, gravity: f32, elaps
         end_pos: Vec2::new(catch_stack_pos.x, catch_stack_pos.y),
         start_time_s: elapsed_time,
         end_time_s: elapsed_time + arc_time,
+        stack_entity,
     };
     commands.entity(entity).insert(flying);
 }
@@ -135,15 +288,24 @@ fn send_fish_to_stack(fish_pos: Vec3, catch_stack_pos: Vec3, gravity: f32, elaps
 fn interpolate_flying_arc(
     mut flying_query: Query<(Entity, &mut Transform, &FlyingToStack, &Fish, &FishLanePos)>,
     time: Res<Time>,
+    species: Res<FishSpeciesList>,
+    (images, image_assets): (Res<ImageHandles>, Res<Assets<Image>>),
     mut on_land: EventWriter<FishLandedInStack>,
 ) {
     for (entity, mut transform, flying, size, lane_pos) in &mut flying_query {
         if time.elapsed_seconds() > flying.end_time_s {
+            let fish_species = &species.species[size.species];
+            let stack_image = images.stack_handle.as_ref().and_then(|handle| image_assets.get(handle));
+            let flight_time = flying.end_time_s - flying.start_time_s;
             on_land.send(FishLandedInStack { 
                 entity, 
                 fish_size: size.size, 
+                stack_height: fish_species.stack_height,
+                contact_width: contact_width(stack_image, fish_species.stack_region),
                 position: flying.end_pos,
-                return_lane_y: lane_pos.pos_y 
+                velocity: Vec2::new(flying.start_vel.x, flying.start_vel.y - flying.gravity * flight_time),
+                return_lane_y: lane_pos.pos_y,
+                stack_entity: flying.stack_entity
             });
             transform.translation = Vec3::new(flying.end_pos.x, flying.end_pos.y, transform.translation.z);
             transform.scale = Vec3::new(1.0, 1.0, 1.0);
@@ -163,31 +325,71 @@ fn handle_fish_landed(
     mut commands: Commands
 ) {
     for event in on_land.iter() {
-        commands.entity(event.entity).remove::<(Handle<TextureAtlas>, FlyingToStack)>();
+        commands.entity(event.entity).remove::<FlyingToStack>();
+        let Ok((stack_pos, stack)) = stack_query.get(event.stack_entity) else {
+            continue;
+        };
+        //a fish that bounced off never made it onto the stack
+        if !stack.fish.iter().any(|fish| fish.entity == event.entity) {
+            continue;
+        }
+        commands.entity(event.entity).remove::<Handle<TextureAtlas>>();
         commands.entity(event.entity).insert((
             images.stack_atlas_handle.as_ref().expect("Images should be loaded").clone(),
             InCatchStack
         ));
         
-        if let Ok((stack_pos, stack)) = stack_query.get_single() {
-            let expected_top_of_stack = 
-                stack_pos.translation.y 
-                + calculate_stack_height(&stack.fish) 
-                - FISH_STACK_SIZES[event.fish_size - 1];
-            if event.position.y > expected_top_of_stack {
-                commands.entity(event.entity).insert(FallingInStack { 
-                    vel_y: 0.0,
-                    final_y: expected_top_of_stack 
+        let expected_top_of_stack = 
+            stack_pos.translation.y 
+            + calculate_stack_height(&stack.fish) 
+            - event.stack_height;
+        if event.position.y > expected_top_of_stack {
+            commands.entity(event.entity).insert(FallingInStack { 
+                vel_y: 0.0,
+                final_y: expected_top_of_stack 
+            });
+        }
+    }
+}
+
+//the widest run of opaque pixels in the fish's region of the stack atlas. the regions
+//are all the same size, so the art is what tells a wide fish from a narrow one
+fn contact_width(stack_image: Option<&Image>, region: Rect) -> f32 {
+    let Some(image) = stack_image else {
+        return region.width();
+    };
+    let size = image.size();
+    let image_width = size.x as usize;
+    //only 8 bit rgba is read, anything else falls back to the whole region
+    if image.data.len() != image_width * size.y as usize * 4 {
+        return region.width();
+    }
+    let min_x = region.min.x.max(0.0) as usize;
+    let max_x = region.max.x.min(size.x) as usize;
+    let min_y = region.min.y.max(0.0) as usize;
+    let max_y = region.max.y.min(size.y) as usize;
+    let mut opaque: Option<(usize, usize)> = None;
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            if image.data[(y * image_width + x) * 4 + 3] > 128 {
+                opaque = Some(match opaque {
+                    Some((left, right)) => (left.min(x), right.max(x)),
+                    None => (x, x)
                 });
             }
         }
     }
+    opaque.map_or(region.width(), |(left, right)| (right - left + 1) as f32)
 }
 
-fn calculate_stack_height(fish: &[Option<StackedFish>]) -> f32 {
+fn stack_sizes(fish: &[StackedFish]) -> Vec<usize> {
+    fish.iter().map(|fish| fish.fish_size).collect()
+}
+
+fn calculate_stack_height(fish: &[StackedFish]) -> f32 {
     let mut height = 0.0;
-    for entries in fish.iter().filter_map(|x| *x) {
-        height += FISH_STACK_SIZES[entries.fish_size - 1];
+    for entries in fish.iter() {
+        height += entries.stack_height;
     }
     height
 }
@@ -195,14 +397,15 @@ fn calculate_stack_height(fish: &[Option<StackedFish>]) -> f32 {
 fn update_falling_fish(
     mut falling_fish: Query<(Entity, &mut Transform, &mut FallingInStack)>,
     time: Res<Time>,
+    tuning: Res<Tuning>,
     mut commands: Commands
 ) {
     for (entity, mut pos, mut falling) in &mut falling_fish {
-        let new_vel_y = falling.vel_y - time.delta_seconds() * GRAVITY;
+        let new_vel_y = falling.vel_y - time.delta_seconds() * tuning.gravity;
         let new_y = 
             pos.translation.y 
             + falling.vel_y * time.delta_seconds()
-            + GRAVITY / 2.0 * time.delta_seconds() * time.delta_seconds();
+            + tuning.gravity / 2.0 * time.delta_seconds() * time.delta_seconds();
         if new_y < falling.final_y {
             pos.translation.y = falling.final_y;
             commands.entity(entity).remove::<FallingInStack>();
@@ -213,6 +416,61 @@ fn update_falling_fish(
     }
 }
 
+//a damped spring pulling the stack upright. leaning too far throws the top fish off,
+//which takes enough weight off for the stack to swing back
+fn wobble_stack(
+    mut stack_query: Query<(&Transform, &mut CatchStack)>,
+    mut fish_query: Query<&mut Transform, (With<InCatchStack>, Without<CatchStack>)>,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    mut on_knocked_out: EventWriter<FishKnockedOutOfStack>
+) {
+    for (stack_pos, mut catch_stack) in &mut stack_query {
+        wobble_one_stack(stack_pos, &mut catch_stack, &mut fish_query, &time, &tuning, &mut on_knocked_out);
+    }
+}
+
+fn wobble_one_stack(
+    stack_pos: &Transform,
+    catch_stack: &mut CatchStack,
+    fish_query: &mut Query<&mut Transform, (With<InCatchStack>, Without<CatchStack>)>,
+    time: &Time,
+    tuning: &Tuning,
+    on_knocked_out: &mut EventWriter<FishKnockedOutOfStack>
+) {
+    let wobble = &tuning.stack_wobble;
+    if catch_stack.fish.is_empty() {
+        catch_stack.sway = 0.0;
+        catch_stack.sway_vel = 0.0;
+        return;
+    }
+    let sway_acc = -wobble.stiffness * catch_stack.sway - wobble.damping * catch_stack.sway_vel;
+    catch_stack.sway_vel += sway_acc * time.delta_seconds();
+    catch_stack.sway += catch_stack.sway_vel * time.delta_seconds();
+
+    if catch_stack.sway.abs() > wobble.topple_angle {
+        let height = calculate_stack_height(&catch_stack.fish);
+        let lean_x = catch_stack.lean_x(height);
+        if let Some(top) = catch_stack.fish.pop() {
+            on_knocked_out.send(FishKnockedOutOfStack {
+                fish_entity: top.entity,
+                stack_position: Vec2::new(stack_pos.translation.x + lean_x, stack_pos.translation.y + height)
+            });
+        }
+        catch_stack.sway = catch_stack.sway.clamp(-wobble.topple_angle, wobble.topple_angle);
+        catch_stack.sway_vel = -catch_stack.sway_vel * 0.5;
+    }
+
+    let mut height = 0.0;
+    for stacked in catch_stack.fish.iter() {
+        if let Ok(mut transform) = fish_query.get_mut(stacked.entity) {
+            transform.translation.x = stack_pos.translation.x + catch_stack.lean_x(height);
+            transform.rotation = Quat::from_rotation_z(-catch_stack.sway);
+        }
+        height += stacked.stack_height;
+    }
+}
+
 #[derive(Event)]
 pub struct FishKnockedOutOfStack {
     pub fish_entity: Entity,
@@ -221,13 +479,15 @@ pub struct FishKnockedOutOfStack {
 
 fn handle_fish_knocked_out_of_stack(
     mut on_knocked_out: EventReader<FishKnockedOutOfStack>,
-    fish_query: Query<(Entity, &FishLanePos), With<InCatchStack>>,
+    mut fish_query: Query<(Entity, &mut Transform, &FishLanePos), With<Fish>>,
     time: Res<Time>,
+    tuning: Res<Tuning>,
     mut commands: Commands
 ) {
     for event in on_knocked_out.iter() {
-        for (fish_entity, lane_pos) in &fish_query {
+        for (fish_entity, mut transform, lane_pos) in &mut fish_query {
             if fish_entity == event.fish_entity {
+                transform.rotation = Quat::IDENTITY;
                 let water_y = WATER_POS.y + WATER_SIZE.y / 2.0;
                 let return_pos = calculate_return_position(lane_pos.pos_y);
                 let return_val = calculate_return_path(
@@ -236,8 +496,8 @@ fn handle_fish_knocked_out_of_stack(
                     return_pos.x, 
                     return_pos.y, 
                     water_y, 
-                    GRAVITY, 
-                    WATER_DRAG_Y, 
+                    tuning.gravity, 
+                    tuning.water_drag_y, 
                     time.elapsed_seconds());
                 commands.entity(fish_entity).remove::<InCatchStack>();
                 commands.entity(fish_entity).insert(return_val);
@@ -246,59 +506,94 @@ fn handle_fish_knocked_out_of_stack(
     }
 }
 
-fn handle_fish_landed_in_stack(
+pub fn handle_fish_landed_in_stack(
     mut on_land: EventReader<FishLandedInStack>,
     mut catch_stack_query: Query<(&Transform, &mut CatchStack)>,
-    popup_query: Query<(), With<PopupTimer>>,
+    state: Res<State<GameState>>,
+    tuning: Res<Tuning>,
+    fish_query: Query<&Fish>,
     mut on_fish_kod: EventWriter<FishKnockedOutOfStack>,
     mut on_complete: EventWriter<StackCompleted>,
 ) {
+    let mut any_landed = false;
     for event in on_land.iter() {
-        let (catch_stack_pos, mut catch_stack) = catch_stack_query.single_mut();
-        let mut indexes_to_remove = Vec::<usize>::new();
-        let mut stack_height = 0.0;
-        for (item_index, item) in catch_stack.fish.iter().enumerate() {
-            if let Some(fish) = item.as_ref() {
-                stack_height += FISH_STACK_SIZES[fish.fish_size - 1];
-                if fish.fish_size < event.fish_size {
-                    let start_pos_y = catch_stack_pos.translation.y + stack_height;
-                    on_fish_kod.send(FishKnockedOutOfStack { 
-                        fish_entity: fish.entity,
-                        stack_position: Vec2::new(catch_stack_pos.translation.x, start_pos_y) 
-                    });
-                    indexes_to_remove.push(item_index);
-                }
-            }
+        let Ok((catch_stack_pos, mut catch_stack)) = catch_stack_query.get_mut(event.stack_entity) else {
+            continue;
+        };
+        any_landed = true;
+        let rule = catch_stack.rule.rule();
+        //how far off the top it came down and how fast it was going sideways both tip
+        //the stack, and a narrow fish underneath gives it less to balance on
+        let top_x = catch_stack_pos.translation.x + catch_stack.lean_x(calculate_stack_height(&catch_stack.fish));
+        let landing_offset = event.position.x - top_x;
+        let wobble = &tuning.stack_wobble;
+        if let Some(support) = catch_stack.fish.last() {
+            let push = wobble.landing_push * event.velocity.x + wobble.offset_push * landing_offset;
+            catch_stack.sway_vel += push / support.contact_width.max(1.0);
         }
-        catch_stack.total_fish = catch_stack.total_fish + 1 - indexes_to_remove.len();
-        for index in indexes_to_remove {
-            catch_stack.fish[index] = None;
+        let landing = rule.land(&stack_sizes(&catch_stack.fish), event.fish_size);
+        if landing.bounced {
+            on_fish_kod.send(FishKnockedOutOfStack {
+                fish_entity: event.entity,
+                stack_position: event.position
+            });
+            continue;
         }
-        let mut insert_offset = 0;
-        let mut first_empty_slot = 0;
-        for i in 0..catch_stack.fish.len() {
-            if catch_stack.fish[i].is_none() {
-                insert_offset += 1;
-            } else if insert_offset > 0 {
-                debug_assert!(insert_offset <= i);
-                catch_stack.fish[i - insert_offset] = catch_stack.fish[i];
-                catch_stack.fish[i] = None;
-                first_empty_slot = i + 1;
+        let sway = catch_stack.sway;
+        let mut stack_height = 0.0;
+        let mut index = 0;
+        catch_stack.fish.retain(|fish| {
+            stack_height += fish.stack_height;
+            index += 1;
+            if landing.knocked_out.contains(&(index - 1)) {
+                let start_pos_x = catch_stack_pos.translation.x + stack_height * sway.sin();
+                let start_pos_y = catch_stack_pos.translation.y + stack_height;
+                on_fish_kod.send(FishKnockedOutOfStack { 
+                    fish_entity: fish.entity,
+                    stack_position: Vec2::new(start_pos_x, start_pos_y) 
+                });
+                false
             } else {
-                first_empty_slot = i + 1;
+                true
             }
-        }
-        catch_stack.fish[first_empty_slot] = Some(
+        });
+        catch_stack.fish.push(
             StackedFish { 
                 entity: event.entity, 
-                fish_size: event.fish_size
+                fish_size: event.fish_size,
+                stack_height: event.stack_height,
+                contact_width: event.contact_width
         });
-        if popup_query.is_empty() && catch_stack.total_fish == FISH_PER_LEVEL {
+    }
+    if any_landed && *state.get() == GameState::Playing {
+        let level_sizes: Vec<usize> = fish_query.iter().map(|fish| fish.size).collect();
+        let stacks: Vec<(StackRules, Vec<usize>)> = catch_stack_query.iter()
+            .map(|(_, stack)| (stack.rule, stack_sizes(&stack.fish)))
+            .collect();
+        if all_stacks_complete(&stacks, &level_sizes) {
             on_complete.send_default();
         }
     }
 }
 
+//each stack is judged on the fish that aren't already on one of the others
+fn all_stacks_complete(stacks: &[(StackRules, Vec<usize>)], level_sizes: &[usize]) -> bool {
+    stacks.iter().enumerate().all(|(index, (rule, sizes))| {
+        let mut available = level_sizes.to_vec();
+        for (other_index, (_, other_sizes)) in stacks.iter().enumerate() {
+            if other_index == index {
+                continue;
+            }
+            for size in other_sizes {
+                if let Some(position) = available.iter().position(|available_size| available_size == size) {
+                    available.remove(position);
+                }
+            }
+        }
+        rule.rule().is_complete(sizes, &available)
+    })
+}
+
 fn calculate_return_position(
     lane_y: f32,
 ) -> Vec2 {
@@ -350,6 +645,42 @@ fn calculate_return_path(
         water_entrance_time_s: start_time_s + time_to_apex + time_from_apex_to_water,
         end_time_s: start_time_s + total_time,
         gravity: gravity_y,
-        water_drag: water_drag_y
+        water_drag: water_drag_y,
+        splashes: true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacks_are_complete_when_the_fish_are_split_between_them() {
+        let level_sizes = [1, 3, 5, 7, 8, 10];
+        let stacks = [
+            (StackRules::Descending, vec![10, 7, 3]),
+            (StackRules::Descending, vec![8, 5, 1]),
+        ];
+        assert!(all_stacks_complete(&stacks, &level_sizes));
+    }
+
+    #[test]
+    fn stacks_are_not_complete_while_a_fish_is_in_the_water() {
+        let level_sizes = [1, 3, 5, 7, 8, 10];
+        let stacks = [
+            (StackRules::Descending, vec![10, 7, 3]),
+            (StackRules::Descending, vec![8, 5]),
+        ];
+        assert!(!all_stacks_complete(&stacks, &level_sizes));
+    }
+
+    #[test]
+    fn an_empty_stack_is_complete_once_the_others_hold_every_fish() {
+        let level_sizes = [3, 6, 9];
+        let stacks = [
+            (StackRules::Descending, vec![9, 6, 3]),
+            (StackRules::Descending, Vec::new()),
+        ];
+        assert!(all_stacks_complete(&stacks, &level_sizes));
     }
 }
@@ -0,0 +1,287 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::{
+    core::in_level,
+    fish::*,
+    hook::{HookInWater, NearFish},
+    physics::Velocity,
+    species::FishSpeciesList,
+    tackle::Tackle,
+    tuning::Tuning
+};
+
+pub struct ChasePlugin;
+impl Plugin for ChasePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, (
+            start_chasing,
+            chase_hook,
+            return_to_lane,
+        ).chain().run_if(in_level));
+    }
+}
+
+/// Takes over a fish's swimming from `FishMovement` while it goes after the hook.
+/// Removing it hands the fish back, with its burst timer where it left off.
+#[derive(Component, Debug, PartialEq, Eq)]
+pub enum FishChase {
+    Chasing,
+    /// Gave up on the hook, heading back to `FishLanePos::pos_y` before swimming normally again.
+    ReturningToLane,
+}
+
+/// How fish go after the hook, from the tuning file.
+#[derive(Deserialize, Clone, Debug)]
+pub struct ChaseTuning {
+    /// How close a fish's mouth has to be to the hook to start chasing it.
+    pub start_range: f32,
+    /// Chasing fish lose interest once the hook is further than this.
+    pub give_up_range: f32,
+    /// How far above or below its lane a fish will follow the hook.
+    pub lane_half_height: f32,
+    pub speed: f32,
+    /// How fast a fish that gave up swims back to its lane.
+    pub return_speed: f32,
+}
+
+impl ChaseTuning {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.start_range <= 0.0 || self.give_up_range < self.start_range {
+            errors.push(format!("chase needs a positive start_range no bigger than give_up_range, got {:?}", self));
+        }
+        if self.lane_half_height <= 0.0 || self.speed <= 0.0 || self.return_speed <= 0.0 {
+            errors.push(format!("chase needs a positive lane_half_height, speed and return_speed, got {:?}", self));
+        }
+        errors
+    }
+}
+
+/// A hook that's in the water and not already taken by another fish.
+type ChaseTargetFilter = (With<HookInWater>, Without<NearFish>, Without<FishChase>);
+type SwimmingFishFilter = (With<FishMovement>, Without<FishChase>, Without<Spooked>);
+
+#[derive(Debug, PartialEq)]
+enum ChaseDecision {
+    /// Where the fish's mouth should head for this frame.
+    MoveTowards(Vec2),
+    GiveUp,
+}
+
+fn in_lane(y: f32, lane_y: f32, chase: &ChaseTuning) -> bool {
+    (y - lane_y).abs() <= chase.lane_half_height
+}
+
+//fish never follow the hook out of their lane, they lose interest instead
+fn decide_chase(mouth_pos: Vec2, lane_y: f32, hook_pos: Option<Vec2>, chase: &ChaseTuning) -> ChaseDecision {
+    match hook_pos {
+        Some(hook_pos) if in_lane(hook_pos.y, lane_y, chase) && mouth_pos.distance(hook_pos) <= chase.give_up_range =>
+            ChaseDecision::MoveTowards(hook_pos),
+        _ => ChaseDecision::GiveUp,
+    }
+}
+
+fn facing(transform: &Transform) -> f32 {
+    transform.scale.x.signum()
+}
+
+fn mouth_position(transform: &Transform, fish: &Fish, species: &FishSpeciesList) -> Vec2 {
+    let mouth_offset = species.species[fish.species].mouth_offset;
+    transform.translation.truncate() + Vec2::new(mouth_offset.x * facing(transform), mouth_offset.y)
+}
+
+fn start_chasing(
+    fish_query: Query<(Entity, &Transform, &Fish, &FishLanePos), SwimmingFishFilter>,
+    hook_query: Query<&Transform, ChaseTargetFilter>,
+    species: Res<FishSpeciesList>,
+    (tackle, tuning): (Res<Tackle>, Res<Tuning>),
+    mut commands: Commands
+) {
+    let Ok(hook_pos) = hook_query.get_single().map(|hook| hook.translation.truncate()) else {
+        return;
+    };
+    for (entity, transform, fish, lane_pos) in &fish_query {
+        if !tackle.will_bite(&species.species[fish.species]) {
+            continue;
+        }
+        let mouth_pos = mouth_position(transform, fish, &species);
+        let is_facing_hook = (hook_pos.x - transform.translation.x) * facing(transform) > 0.0;
+        if is_facing_hook
+            && in_lane(hook_pos.y, lane_pos.pos_y, &tuning.chase)
+            && mouth_pos.distance(hook_pos) <= tuning.chase.start_range {
+            commands.entity(entity).insert(FishChase::Chasing);
+        }
+    }
+}
+
+fn chase_hook(
+    mut fish_query: Query<(&mut Transform, &mut FishChase, &mut Velocity, &Fish, &FishLanePos)>,
+    hook_query: Query<&Transform, ChaseTargetFilter>,
+    species: Res<FishSpeciesList>,
+    (time, tuning): (Res<Time>, Res<Tuning>)
+) {
+    let chase_tuning = &tuning.chase;
+    let hook_pos = hook_query.get_single().ok().map(|hook| hook.translation.truncate());
+    for (mut transform, mut chase, mut velocity, fish, lane_pos) in &mut fish_query {
+        if *chase != FishChase::Chasing {
+            continue;
+        }
+        //the chase moves the fish itself, so the physics shouldn't add anything on top
+        velocity.x = 0.0;
+        velocity.y = 0.0;
+        let mouth_pos = mouth_position(&transform, fish, &species);
+        match decide_chase(mouth_pos, lane_pos.pos_y, hook_pos, chase_tuning) {
+            ChaseDecision::MoveTowards(target) => {
+                let to_target = target - mouth_pos;
+                let step = chase_tuning.speed * time.delta_seconds();
+                let movement = if to_target.length() <= step { to_target } else { to_target.normalize() * step };
+                transform.translation += movement.extend(0.0);
+                transform.translation.y = transform.translation.y
+                    .clamp(lane_pos.pos_y - chase_tuning.lane_half_height, lane_pos.pos_y + chase_tuning.lane_half_height);
+                if to_target.x * facing(&transform) < 0.0 {
+                    transform.scale.x *= -1.0;
+                }
+            },
+            ChaseDecision::GiveUp => *chase = FishChase::ReturningToLane,
+        }
+    }
+}
+
+fn return_to_lane(
+    mut fish_query: Query<(Entity, &mut Transform, &FishChase, &mut FishMovement, &FishLanePos)>,
+    (time, tuning): (Res<Time>, Res<Tuning>),
+    mut commands: Commands
+) {
+    for (entity, mut transform, chase, mut movement, lane_pos) in &mut fish_query {
+        if *chase != FishChase::ReturningToLane {
+            continue;
+        }
+        let to_lane = lane_pos.pos_y - transform.translation.y;
+        let step = tuning.chase.return_speed * time.delta_seconds();
+        if to_lane.abs() <= step {
+            transform.translation.y = lane_pos.pos_y;
+            //the next burst should go the way the fish ended up facing
+            movement.vel_to_apply = movement.vel_to_apply.abs() * facing(&transform);
+            commands.entity(entity).remove::<FishChase>();
+        } else {
+            transform.translation.y += step * to_lane.signum();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use super::*;
+
+    const LANE_Y: f32 = -300.0;
+
+    fn tuning() -> Tuning {
+        ron::de::from_str(include_str!("../assets/game.tuning.ron")).expect("tuning should parse")
+    }
+
+    #[test]
+    fn keeps_chasing_a_hook_inside_the_lane() {
+        let chase = tuning().chase;
+        let hook_pos = Vec2::new(100.0, LANE_Y + chase.lane_half_height / 2.0);
+        assert_eq!(
+            decide_chase(Vec2::new(0.0, LANE_Y), LANE_Y, Some(hook_pos), &chase),
+            ChaseDecision::MoveTowards(hook_pos));
+    }
+
+    #[test]
+    fn gives_up_when_the_hook_leaves_the_lane() {
+        let chase = tuning().chase;
+        let above_lane = Vec2::new(50.0, LANE_Y + chase.lane_half_height + 1.0);
+        let below_lane = Vec2::new(50.0, LANE_Y - chase.lane_half_height - 1.0);
+        assert_eq!(decide_chase(Vec2::new(0.0, LANE_Y), LANE_Y, Some(above_lane), &chase), ChaseDecision::GiveUp);
+        assert_eq!(decide_chase(Vec2::new(0.0, LANE_Y), LANE_Y, Some(below_lane), &chase), ChaseDecision::GiveUp);
+    }
+
+    #[test]
+    fn gives_up_when_the_hook_gets_away() {
+        let chase = tuning().chase;
+        let far_hook = Vec2::new(chase.give_up_range + 1.0, LANE_Y);
+        assert_eq!(decide_chase(Vec2::new(0.0, LANE_Y), LANE_Y, Some(far_hook), &chase), ChaseDecision::GiveUp);
+    }
+
+    #[test]
+    fn gives_up_when_the_hook_leaves_the_water() {
+        assert_eq!(decide_chase(Vec2::new(0.0, LANE_Y), LANE_Y, None, &tuning().chase), ChaseDecision::GiveUp);
+    }
+
+    fn build_app() -> App {
+        let mut app = App::new();
+        let species: FishSpeciesList = ron::de::from_str(include_str!("../assets/fish.species.ron"))
+            .expect("fish species should parse");
+        app
+        .insert_resource(species)
+        .insert_resource(tuning())
+        .insert_resource(Time::default())
+        .add_systems(Update, (chase_hook, return_to_lane).chain());
+        app
+    }
+
+    fn advance_time(app: &mut App, seconds: f32) {
+        let mut time = app.world.resource_mut::<Time>();
+        let last_update = time.last_update().unwrap_or_else(Instant::now);
+        time.update_with_instant(last_update + Duration::from_secs_f32(seconds));
+    }
+
+    fn spawn_chasing_fish(app: &mut App, y: f32) -> Entity {
+        app.world.spawn((
+            Transform::from_translation(Vec3::new(0.0, y, 0.0)).with_scale(Vec3::new(-1.0, 1.0, 1.0)),
+            FishChase::Chasing,
+            FishMovement {
+                next_move_time: Timer::from_seconds(5.0, TimerMode::Repeating),
+                vel_to_apply: 500.0
+            },
+            Velocity { x: 200.0, y: 0.0, drag_x: 50.0, drag_y: 12000.0 },
+            Fish { size: 1, species: 0 },
+            FishLanePos { pos_y: LANE_Y }
+        )).id()
+    }
+
+    #[test]
+    fn swims_back_to_its_lane_and_hands_back_control_after_giving_up() {
+        let lane_half_height = tuning().chase.lane_half_height;
+        let mut app = build_app();
+        app.world.spawn((
+            Transform::from_translation(Vec3::new(0.0, LANE_Y + lane_half_height * 3.0, 0.0)),
+            HookInWater { surface_x: 0.0 }
+        ));
+        let fish = spawn_chasing_fish(&mut app, LANE_Y + lane_half_height / 2.0);
+
+        advance_time(&mut app, 0.0);
+        app.update();
+        assert_eq!(app.world.get::<FishChase>(fish), Some(&FishChase::ReturningToLane));
+
+        advance_time(&mut app, 0.1);
+        app.update();
+        let y = app.world.get::<Transform>(fish).unwrap().translation.y;
+        assert!(y < LANE_Y + lane_half_height / 2.0 && y > LANE_Y, "should be partway back, was at {}", y);
+
+        advance_time(&mut app, 10.0);
+        app.update();
+        assert_eq!(app.world.get::<FishChase>(fish), None);
+        assert_eq!(app.world.get::<Transform>(fish).unwrap().translation.y, LANE_Y);
+        //facing left now, so the next burst goes left
+        assert_eq!(app.world.get::<FishMovement>(fish).unwrap().vel_to_apply, -500.0);
+    }
+
+    #[test]
+    fn gives_up_when_the_hook_is_taken_by_another_fish() {
+        let mut app = build_app();
+        app.world.spawn((
+            Transform::from_translation(Vec3::new(0.0, LANE_Y, 0.0)),
+            HookInWater { surface_x: 0.0 },
+            NearFish
+        ));
+        let fish = spawn_chasing_fish(&mut app, LANE_Y + 10.0);
+
+        advance_time(&mut app, 0.0);
+        app.update();
+        assert_eq!(app.world.get::<FishChase>(fish), Some(&FishChase::ReturningToLane));
+    }
+}
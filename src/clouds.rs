@@ -1,6 +1,8 @@
 use bevy::prelude::*;
+use rand::Rng;
 use crate::core::*;
 use crate::constants::*;
+use crate::rng::GameRng;
 
 pub struct CloudsPlugin;
 impl Plugin for CloudsPlugin {
@@ -18,26 +20,28 @@ pub struct Cloud {
 
 fn add_clouds(
     images: Res<ImageHandles>,
+    mut rng: ResMut<GameRng>,
     mut commands: Commands
 ) {
     let texture_handle = images.misc_atlas_handle.as_ref().expect("Images should be loaded").clone();
-    commands.spawn_batch(
+    let clouds: Vec<_> =
         (0..10)
-        .map(move |i| (
+        .map(|i| (
             SpriteSheetBundle {
                 texture_atlas: texture_handle.clone(),
                 sprite: TextureAtlasSprite::new(i%6),
                 transform: Transform::from_translation(
                     Vec3::new(
-                        (CLOUD_END_X - CLOUD_START_X) * rand::random::<f32>() + CLOUD_START_X ,
-                        rand::random::<f32>() * 300.0 + CLOUD_Y,
+                        (CLOUD_END_X - CLOUD_START_X) * rng.gen::<f32>() + CLOUD_START_X ,
+                        rng.gen::<f32>() * 300.0 + CLOUD_Y,
                         i as f32 - 20.0
                     )),
                 ..default()
             },
-            Cloud { speed: 50.0 + rand::random::<f32>() * 10.0 }
+            Cloud { speed: 50.0 + rng.gen::<f32>() * 10.0 }
         ))
-    );
+        .collect();
+    commands.spawn_batch(clouds);
 }
 
 fn update_clouds(
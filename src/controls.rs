@@ -0,0 +1,269 @@
+use std::{collections::BTreeMap, time::Duration};
+use bevy::{input::{InputSystem, gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest}}, prelude::*};
+use serde::{Deserialize, Serialize};
+use crate::{catch_stack::FishKnockedOutOfStack, hook::{FishCaught, HookedFish}, settings::Settings};
+
+/// Turns the keyboard and gamepads into `Input<Action>` and `ActionAxes`, so
+/// gameplay doesn't care what the player is holding. Also rumbles the gamepads.
+pub struct ControlsPlugin;
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(Input::<Action>::default())
+        .insert_resource(ActionAxes::default())
+        .add_systems(PreUpdate, update_actions.after(InputSystem))
+        .add_systems(PostUpdate, (
+            rumble_on_bite,
+            rumble_on_critical_catch,
+            rumble_on_knocked_out,
+        ));
+    }
+}
+
+/// Everything the player can do in a level.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum Action {
+    HookUp,
+    HookDown,
+    HookLeft,
+    HookRight,
+    /// Charges and releases the cast, sets the hook, reels in and continues past the popups.
+    Cast,
+    SwapBait,
+    SwapHook,
+    /// Picks which stack the next fish reeled to the surface flies to.
+    SwitchStack,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::HookUp,
+        Action::HookDown,
+        Action::HookLeft,
+        Action::HookRight,
+        Action::Cast,
+        Action::SwapBait,
+        Action::SwapHook,
+        Action::SwitchStack,
+        Action::Pause,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::HookUp => "HOOK UP",
+            Action::HookDown => "HOOK DOWN",
+            Action::HookLeft => "HOOK LEFT",
+            Action::HookRight => "HOOK RIGHT",
+            Action::Cast => "CAST AND REEL",
+            Action::SwapBait => "SWAP BAIT",
+            Action::SwapHook => "SWAP HOOK",
+            Action::SwitchStack => "SWITCH STACK",
+            Action::Pause => "PAUSE",
+        }
+    }
+
+    fn default_keys(&self) -> &'static [KeyCode] {
+        match self {
+            Action::HookUp => &[KeyCode::W, KeyCode::Up],
+            Action::HookDown => &[KeyCode::S, KeyCode::Down],
+            Action::HookLeft => &[KeyCode::A, KeyCode::Left],
+            Action::HookRight => &[KeyCode::D, KeyCode::Right],
+            Action::Cast => &[KeyCode::Space, KeyCode::Return],
+            Action::SwapBait => &[KeyCode::Q],
+            Action::SwapHook => &[KeyCode::E],
+            Action::SwitchStack => &[KeyCode::Tab],
+            Action::Pause => &[KeyCode::Escape],
+        }
+    }
+
+    fn default_buttons(&self) -> &'static [GamepadButtonType] {
+        match self {
+            Action::HookUp => &[GamepadButtonType::DPadUp],
+            Action::HookDown => &[GamepadButtonType::DPadDown],
+            Action::HookLeft => &[GamepadButtonType::DPadLeft],
+            Action::HookRight => &[GamepadButtonType::DPadRight],
+            Action::Cast => &[GamepadButtonType::South],
+            Action::SwapBait => &[GamepadButtonType::West],
+            Action::SwapHook => &[GamepadButtonType::North],
+            Action::SwitchStack => &[GamepadButtonType::RightTrigger],
+            Action::Pause => &[GamepadButtonType::Start],
+        }
+    }
+}
+
+/// Which keys and gamepad buttons trigger each action, saved with the `Settings`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Bindings {
+    keys: BTreeMap<Action, Vec<KeyCode>>,
+    buttons: BTreeMap<Action, Vec<GamepadButtonType>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings {
+            keys: Action::ALL.iter().map(|action| (*action, action.default_keys().to_vec())).collect(),
+            buttons: Action::ALL.iter().map(|action| (*action, action.default_buttons().to_vec())).collect(),
+        }
+    }
+}
+
+impl Bindings {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], |keys| keys.as_slice())
+    }
+
+    pub fn buttons(&self, action: Action) -> &[GamepadButtonType] {
+        self.buttons.get(&action).map_or(&[], |buttons| buttons.as_slice())
+    }
+
+    /// Actions added since the bindings were saved get their defaults.
+    pub fn fill_missing(&mut self) {
+        let defaults = Bindings::default();
+        for action in Action::ALL {
+            self.keys.entry(action).or_insert_with(|| defaults.keys(action).to_vec());
+            self.buttons.entry(action).or_insert_with(|| defaults.buttons(action).to_vec());
+        }
+    }
+
+    /// Makes `key` the only key for `action`, see `bind` for what happens on a conflict.
+    pub fn bind_key(&mut self, action: Action, key: KeyCode) -> Option<Action> {
+        bind(&mut self.keys, action, key)
+    }
+
+    pub fn bind_button(&mut self, action: Action, button: GamepadButtonType) -> Option<Action> {
+        bind(&mut self.buttons, action, button)
+    }
+
+    pub fn describe_keys(&self, action: Action) -> String {
+        describe(self.keys(action))
+    }
+
+    pub fn describe_buttons(&self, action: Action) -> String {
+        describe(self.buttons(action))
+    }
+}
+
+//an input can only do one thing, so it's taken off whichever action had it. if that
+//leaves the other action with nothing, it gets the keys `action` just gave up instead
+fn bind<T: Copy + PartialEq>(bindings: &mut BTreeMap<Action, Vec<T>>, action: Action, input: T) -> Option<Action> {
+    let old_inputs = bindings.insert(action, vec![input]).unwrap_or_default();
+    let (conflict, other_inputs) = bindings.iter_mut()
+        .find(|(other, inputs)| **other != action && inputs.contains(&input))?;
+    other_inputs.retain(|other_input| *other_input != input);
+    if other_inputs.is_empty() {
+        *other_inputs = old_inputs.into_iter().filter(|old_input| *old_input != input).collect();
+    }
+    Some(*conflict)
+}
+
+pub fn describe_input(input: impl std::fmt::Debug) -> String {
+    format!("{:?}", input).to_uppercase()
+}
+
+fn describe<T: std::fmt::Debug>(inputs: &[T]) -> String {
+    if inputs.is_empty() {
+        return "-".to_string();
+    }
+    inputs.iter().map(describe_input).collect::<Vec<_>>().join(" / ")
+}
+
+/// The analog side of the controls, with the buttons for the same things folded in.
+#[derive(Resource, Default, Debug)]
+pub struct ActionAxes {
+    /// Which way to move the hook, each axis from -1 to 1.
+    pub hook_movement: Vec2,
+    /// How hard to reel, from 0 to 1. `Action::Cast` reels at full speed, the right trigger can go slower.
+    pub reel: f32,
+}
+
+const STICK_DEADZONE: f32 = 0.2;
+
+fn update_actions(
+    settings: Res<Settings>,
+    keys: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    (axes, button_axes): (Res<Axis<GamepadAxis>>, Res<Axis<GamepadButton>>),
+    mut actions: ResMut<Input<Action>>,
+    mut action_axes: ResMut<ActionAxes>
+) {
+    actions.clear();
+    let bindings = &settings.bindings;
+    for action in Action::ALL {
+        let held = bindings.keys(action).iter().any(|key| keys.pressed(*key))
+            || gamepads.iter().any(|gamepad| bindings.buttons(action).iter()
+                .any(|button| buttons.pressed(GamepadButton::new(gamepad, *button))));
+        if held {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+
+    let button_axis = |negative: Action, positive: Action|
+        if actions.pressed(positive) { 1.0 } else { 0.0 } + if actions.pressed(negative) { -1.0 } else { 0.0 };
+    let mut hook_movement = Vec2::new(
+        button_axis(Action::HookLeft, Action::HookRight),
+        button_axis(Action::HookDown, Action::HookUp));
+    let mut reel: f32 = if actions.pressed(Action::Cast) { 1.0 } else { 0.0 };
+    for gamepad in gamepads.iter() {
+        let stick = Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)).unwrap_or(0.0),
+            axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY)).unwrap_or(0.0));
+        if stick.length() > STICK_DEADZONE {
+            hook_movement += stick;
+        }
+        let trigger = button_axes.get(GamepadButton::new(gamepad, GamepadButtonType::RightTrigger2)).unwrap_or(0.0);
+        reel = reel.max(trigger);
+    }
+    action_axes.hook_movement = hook_movement.clamp(Vec2::NEG_ONE, Vec2::ONE);
+    action_axes.reel = reel;
+}
+
+fn rumble_all(
+    gamepads: &Gamepads,
+    on_rumble: &mut EventWriter<GamepadRumbleRequest>,
+    intensity: GamepadRumbleIntensity,
+    duration_s: f32
+) {
+    for gamepad in gamepads.iter() {
+        on_rumble.send(GamepadRumbleRequest::Add {
+            gamepad,
+            intensity,
+            duration: Duration::from_secs_f32(duration_s)
+        });
+    }
+}
+
+fn rumble_on_bite(
+    mut on_hook: EventReader<HookedFish>,
+    gamepads: Res<Gamepads>,
+    mut on_rumble: EventWriter<GamepadRumbleRequest>
+) {
+    if !on_hook.is_empty() {
+        on_hook.clear();
+        rumble_all(&gamepads, &mut on_rumble, GamepadRumbleIntensity::weak_motor(0.6), 0.15);
+    }
+}
+
+fn rumble_on_critical_catch(
+    mut on_catch: EventReader<FishCaught>,
+    gamepads: Res<Gamepads>,
+    mut on_rumble: EventWriter<GamepadRumbleRequest>
+) {
+    if on_catch.iter().any(|event| event.grade.is_critical()) {
+        rumble_all(&gamepads, &mut on_rumble, GamepadRumbleIntensity::MAX, 0.4);
+    }
+}
+
+fn rumble_on_knocked_out(
+    mut on_knocked_out: EventReader<FishKnockedOutOfStack>,
+    gamepads: Res<Gamepads>,
+    mut on_rumble: EventWriter<GamepadRumbleRequest>
+) {
+    if !on_knocked_out.is_empty() {
+        on_knocked_out.clear();
+        rumble_all(&gamepads, &mut on_rumble, GamepadRumbleIntensity::strong_motor(0.8), 0.3);
+    }
+}
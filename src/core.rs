@@ -1,13 +1,15 @@
-use bevy::{prelude::*, sprite::Anchor};
-use crate::{constants::*, snail::{SnailReachedEnd, SnailStarted}, catch_stack::StackCompleted, audio::MusicLoaded};
+use bevy::{prelude::*, asset::HandleId, sprite::Anchor};
+use serde::{Deserialize, Serialize};
+use crate::{constants::*, controls::Action, daily::DailyRun, pointer::Pointer, rng::SeedConfig, snail::{SnailReachedEnd, SnailStarted}, catch_stack::StackCompleted, level::CurrentLevel, species::FishSpeciesList};
 
 pub struct CorePlugin;
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app
+        .add_state::<GameState>()
         .add_event::<ResetLevel>()
-        .add_event::<MusicLoaded>()
         .insert_resource(GameTimer::default())
+        .insert_resource(GameMode::default())
         .insert_resource(ImageHandles::default())
         .insert_resource(FontHandles::default())
         .add_systems(PreStartup, (
@@ -20,14 +22,68 @@ impl Plugin for CorePlugin {
             add_water,
         ))
         .add_systems(Update, (
-            wait_to_reset,
-            update_game_timer,
+            wait_to_reset.run_if(in_state(GameState::Won).or_else(in_state(GameState::Lost))),
+            update_game_timer.run_if(in_state(GameState::Playing)),
         ))
         .add_systems(PostUpdate, (
             handle_snail_start,
-            handle_loss,
-            handle_win,
-        ));
+            reset_game_timer,
+            handle_loss.run_if(in_state(GameState::Playing)),
+            handle_win.run_if(in_state(GameState::Playing)),
+        ))
+        .add_systems(OnEnter(GameState::Won), show_win_popup)
+        .add_systems(OnEnter(GameState::Lost), show_loss_popup)
+        .add_systems(OnExit(GameState::Won), despawn_popups)
+        .add_systems(OnExit(GameState::Lost), despawn_popups);
+    }
+}
+
+/// Top level flow of the game. Gameplay systems gate themselves on these
+/// with `run_if(in_state(..))`, see `in_level` for the ones that keep
+/// running behind the win/lose popups.
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum GameState {
+    #[default]
+    Loading,
+    /// Something in `ImageHandles`, `FontHandles` or `AudioHandles` failed to load.
+    LoadFailed,
+    Title,
+    Playing,
+    Paused,
+    Won,
+    Lost,
+}
+
+/// Schedule for spawning what needs the loaded assets. `OnExit(GameState::Loading)`
+/// would also run on the way to `LoadFailed`, with handles that may not have loaded.
+pub const ASSETS_LOADED: OnTransition<GameState> = OnTransition { from: GameState::Loading, to: GameState::Title };
+
+/// Run condition for systems that animate the level, which should keep going
+/// behind the title menu and the win/lose popups but not before the level exists.
+pub fn in_level(state: Res<State<GameState>>) -> bool {
+    !matches!(state.get(), GameState::Loading | GameState::LoadFailed)
+}
+
+/// The rules the next level is played under, picked from the title screen.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    /// Seeded from the local date, only the first attempt each day counts.
+    Daily,
+    /// A run through several levels, see `campaign_levels` in the tuning file.
+    Campaign,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 3] = [GameMode::Classic, GameMode::Daily, GameMode::Campaign];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "CLASSIC",
+            GameMode::Daily => "DAILY CHALLENGE",
+            GameMode::Campaign => "CAMPAIGN",
+        }
     }
 }
 
@@ -52,11 +108,34 @@ pub struct ImageHandles {
     pub misc_atlas_handle: Option<Handle<TextureAtlas>>
 }
 
+impl ImageHandles {
+    /// The images loaded from disk, the atlases are built from these so aren't included.
+    pub fn ids(&self) -> Vec<HandleId> {
+        [
+            &self.fish_handle,
+            &self.hook_handle,
+            &self.bg_handle,
+            &self.bear_handle,
+            &self.stack_handle,
+            &self.water_handle,
+            &self.misc_handle,
+            &self.win_bubble_handle,
+            &self.lose_bubble_handle,
+        ].into_iter().flatten().map(|handle| handle.id()).collect()
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct FontHandles {
     pub timer_font_handle: Option<Handle<Font>>
 }
 
+impl FontHandles {
+    pub fn ids(&self) -> Vec<HandleId> {
+        self.timer_font_handle.iter().map(|handle| handle.id()).collect()
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct GameTimer{
     pub running: bool,
@@ -68,37 +147,28 @@ pub struct PopupTimer {
     pub timer: Timer
 }
 
+/// Marks everything spawned for a win/lose popup, so it can be despawned on exit.
+#[derive(Component)]
+pub struct Popup;
+
 fn load_images(
     mut images: ResMut<ImageHandles>,
     mut atlases: ResMut<Assets<TextureAtlas>>,
+    species: Res<FishSpeciesList>,
     asset_server: Res<AssetServer>
 ) {
     images.bg_handle = Some(asset_server.load("background.png"));
     images.hook_handle = Some(asset_server.load("hook.png"));
     images.water_handle = Some(asset_server.load("water.png"));
 
-    let fish_handle = asset_server.load("fish_atlas.png");
-    let fish_atlas = TextureAtlas::from_grid(
-        fish_handle.clone(),
-        Vec2::new(600.0, 200.0),
-        2,
-        5,
-        None, 
-        None
-    );
+    let fish_handle = asset_server.load(species.fish_sheet.path.as_str());
+    let fish_atlas = species.build_fish_atlas(fish_handle.clone());
     images.fish_handle = Some(fish_handle);
     let fish_atlas_handle = atlases.add(fish_atlas);
     images.fish_atlas_handle = Some(fish_atlas_handle);
 
-    let stack_handle = asset_server.load("stack_atlas.png");
-    let stack_atlas = TextureAtlas::from_grid(
-        stack_handle.clone(),
-        Vec2::new(600.0, 200.0),
-        2,
-        5,
-        None, 
-        None
-    );
+    let stack_handle = asset_server.load(species.stack_sheet.path.as_str());
+    let stack_atlas = species.build_stack_atlas(stack_handle.clone());
     images.stack_handle = Some(stack_handle);
     let fish_atlas_handle = atlases.add(stack_atlas);
     images.stack_atlas_handle = Some(fish_atlas_handle);
@@ -176,70 +246,107 @@ fn add_water(
 
 fn handle_loss(
     mut on_snail_end: EventReader<SnailReachedEnd>,
-    images: Res<ImageHandles>,
-    mut commands: Commands
+    mut next_state: ResMut<NextState<GameState>>
 ) {
     if !on_snail_end.is_empty() {
         on_snail_end.clear();
         println!("GAME OVER");
-        commands.spawn((
-            SpriteBundle {
-                texture: images.lose_bubble_handle.as_ref().expect("images should be loaded").clone(),
-                transform: Transform::from_translation(Vec3::new(0.0, 0.0, 100.0))
-                    .with_scale(Vec3::ONE * 2.0),
-                ..default()
-            },
-            PopupTimer {
-                timer: Timer::from_seconds(0.7, TimerMode::Once)
-            }
-        ));
+        next_state.set(GameState::Lost);
     }
 }
 
+fn show_loss_popup(
+    images: Res<ImageHandles>,
+    mut commands: Commands
+) {
+    commands.spawn((
+        SpriteBundle {
+            texture: images.lose_bubble_handle.as_ref().expect("images should be loaded").clone(),
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 100.0))
+                .with_scale(Vec3::ONE * 2.0),
+            ..default()
+        },
+        PopupTimer {
+            timer: Timer::from_seconds(0.7, TimerMode::Once)
+        },
+        Popup
+    ));
+}
+
 fn wait_to_reset(
-    mut popup_query: Query<(Entity, &mut PopupTimer)>,
-    text_query: Query<Entity, With<Text>>,
+    mut popup_query: Query<&mut PopupTimer>,
     time: Res<Time>,
-    input: Res<Input<KeyCode>>,
-    mut commands: Commands,
+    (actions, pointer): (Res<Input<Action>>, Res<Pointer>),
+    game_timer: Res<GameTimer>,
+    (mut current_level, seed_config, mut daily_run): (ResMut<CurrentLevel>, Res<SeedConfig>, ResMut<DailyRun>),
+    (state, mut next_state): (Res<State<GameState>>, ResMut<NextState<GameState>>),
     mut on_reset: EventWriter<ResetLevel>
 ) {
-    for (entity, mut timer) in &mut popup_query {
+    for mut timer in &mut popup_query {
         timer.timer.tick(time.delta());
-        if timer.timer.finished() && input.pressed(KeyCode::Space) {
-            commands.entity(entity).despawn();
-            on_reset.send_default();
-            if let Ok(text) = text_query.get_single() {
-                commands.entity(text).despawn();
+        if timer.timer.finished() && (actions.pressed(Action::Cast) || pointer.just_pressed) {
+            //a loss retries the same level, a win moves on or starts the run over once it's done
+            let mut new_state = GameState::Playing;
+            if *state.get() == GameState::Won {
+                if current_level.is_last() {
+                    if current_level.mode == GameMode::Campaign {
+                        new_state = GameState::Title;
+                    }
+                    let run_seed = seed_config.new_run_seed(current_level.mode, &mut daily_run);
+                    current_level.restart(run_seed);
+                } else {
+                    current_level.advance(game_timer.total_time_s);
+                }
             }
+            on_reset.send_default();
+            next_state.set(new_state);
         }
     }
 }
 
+fn despawn_popups(
+    popup_query: Query<Entity, With<Popup>>,
+    mut commands: Commands
+) {
+    for entity in &popup_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 fn handle_win(
     mut on_stack_completed: EventReader<StackCompleted>,
     mut game_timer: ResMut<GameTimer>,
-    images: Res<ImageHandles>,
-    fonts: Res<FontHandles>,
-    mut commands: Commands
+    mut next_state: ResMut<NextState<GameState>>
 ) {
     if !on_stack_completed.is_empty() {
         on_stack_completed.clear();
         println!("YOU WIN");
         game_timer.running = false;
-        commands.spawn((
-            SpriteBundle {
-                texture: images.win_bubble_handle.as_ref().expect("Images should be loaded").clone(),
-                transform: Transform::from_translation(Vec3::new(0.0, 0.0, 100.0))
-                    .with_scale(Vec3::ONE * 2.0),
-                ..default()
-            },
-            PopupTimer {
-                timer: Timer::from_seconds(1.0, TimerMode::Once)
-            }
-        ));
-        let time_string = format!("{:.1} s", game_timer.total_time_s);
-        commands.spawn(Text2dBundle {
+        next_state.set(GameState::Won);
+    }
+}
+
+fn show_win_popup(
+    game_timer: Res<GameTimer>,
+    images: Res<ImageHandles>,
+    fonts: Res<FontHandles>,
+    mut commands: Commands
+) {
+    commands.spawn((
+        SpriteBundle {
+            texture: images.win_bubble_handle.as_ref().expect("Images should be loaded").clone(),
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 100.0))
+                .with_scale(Vec3::ONE * 2.0),
+            ..default()
+        },
+        PopupTimer {
+            timer: Timer::from_seconds(1.0, TimerMode::Once)
+        },
+        Popup
+    ));
+    let time_string = format!("{:.1} s", game_timer.total_time_s);
+    commands.spawn((
+        Text2dBundle {
             text: Text::from_section(time_string, TextStyle {
                 font: fonts.timer_font_handle.as_ref().expect("Fonts should be loaded").clone(),
                 font_size: 160.00,
@@ -248,9 +355,10 @@ fn handle_win(
             text_anchor: Anchor::CenterLeft,
             transform: Transform::from_translation(Vec3::new(20.0, -230.0, 101.0)),
             ..default()
-        });
-    }
-} 
+        },
+        Popup
+    ));
+}
 
 fn handle_snail_start(
     mut on_snail_start: EventReader<SnailStarted>,
@@ -263,6 +371,17 @@ fn handle_snail_start(
     }
 }
 
+fn reset_game_timer(
+    mut on_reset: EventReader<ResetLevel>,
+    mut game_time: ResMut<GameTimer>
+) {
+    if !on_reset.is_empty() {
+        on_reset.clear();
+        game_time.running = false;
+        game_time.total_time_s = 0.0;
+    }
+}
+
 fn update_game_timer(
     mut game_time: ResMut<GameTimer>,
     time: Res<Time>
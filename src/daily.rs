@@ -0,0 +1,133 @@
+use bevy::{prelude::*, sprite::Anchor};
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::{core::*, snail::SnailStarted, storage};
+
+pub struct DailyPlugin;
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(storage::load::<DailyResults>(DAILY_RESULTS_KEY).unwrap_or_default())
+        .insert_resource(DailyRun { is_official: false, date: today() })
+        .add_systems(PostUpdate, handle_daily_attempt_started)
+        .add_systems(OnEnter(GameState::Won), (
+            record_daily_win,
+            show_daily_results,
+        ).chain().run_if(resource_equals(GameMode::Daily)))
+        .add_systems(OnEnter(GameState::Lost),
+            show_daily_results.run_if(resource_equals(GameMode::Daily)));
+    }
+}
+
+const DAILY_RESULTS_KEY: &str = "daily_results";
+const DAILY_HISTORY_SHOWN: usize = 5;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DailyResult {
+    pub date: NaiveDate,
+    /// `None` until the official attempt is won, so a loss or a quit reads as a DNF.
+    pub time_s: Option<f32>
+}
+
+/// The official result for every day a daily challenge was attempted, oldest first.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct DailyResults {
+    pub results: Vec<DailyResult>
+}
+
+impl DailyResults {
+    pub fn for_date(&self, date: NaiveDate) -> Option<&DailyResult> {
+        self.results.iter().find(|result| result.date == date)
+    }
+}
+
+/// The daily run in progress.
+#[derive(Resource)]
+pub struct DailyRun {
+    /// Whether it's the player's first attempt of the day.
+    pub is_official: bool,
+    /// Pinned when the run starts, see `SeedConfig::new_run_seed`.
+    pub date: NaiveDate
+}
+
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Everyone playing on the same calendar day gets the same seed, e.g. 20231118.
+pub fn daily_seed(date: NaiveDate) -> u64 {
+    date.year() as u64 * 10000 + date.month() as u64 * 100 + date.day() as u64
+}
+
+//the attempt counts as soon as the first cast starts the clock, so quitting can't be used for a retry
+fn handle_daily_attempt_started(
+    mut on_snail_start: EventReader<SnailStarted>,
+    mode: Res<GameMode>,
+    mut daily_results: ResMut<DailyResults>,
+    mut daily_run: ResMut<DailyRun>
+) {
+    if !on_snail_start.is_empty() {
+        on_snail_start.clear();
+        if *mode != GameMode::Daily {
+            return;
+        }
+        let date = daily_run.date;
+        daily_run.is_official = daily_results.for_date(date).is_none();
+        if daily_run.is_official {
+            daily_results.results.push(DailyResult { date, time_s: None });
+            storage::save(DAILY_RESULTS_KEY, &*daily_results);
+        }
+    }
+}
+
+fn record_daily_win(
+    game_timer: Res<GameTimer>,
+    daily_run: Res<DailyRun>,
+    mut daily_results: ResMut<DailyResults>
+) {
+    if daily_run.is_official {
+        let date = daily_run.date;
+        if let Some(result) = daily_results.results.iter_mut().find(|result| result.date == date) {
+            result.time_s = Some(game_timer.total_time_s);
+        }
+        storage::save(DAILY_RESULTS_KEY, &*daily_results);
+    }
+}
+
+fn show_daily_results(
+    daily_results: Res<DailyResults>,
+    daily_run: Res<DailyRun>,
+    fonts: Res<FontHandles>,
+    mut commands: Commands
+) {
+    let date = daily_run.date;
+    let format_time = |time_s: Option<f32>| time_s.map_or("DNF".to_string(), |time_s| format!("{:.1} s", time_s));
+    let mut lines = vec![
+        format!("DAILY {}", date.format("%Y-%m-%d")),
+        if daily_run.is_official {
+            "OFFICIAL RUN".to_string()
+        } else {
+            let official = daily_results.for_date(date).and_then(|result| result.time_s);
+            format!("PRACTICE, OFFICIAL {}", format_time(official))
+        },
+        String::new(),
+    ];
+    lines.extend(
+        daily_results.results.iter()
+            .rev()
+            .take(DAILY_HISTORY_SHOWN)
+            .map(|result| format!("{}  {}", result.date.format("%m-%d"), format_time(result.time_s))));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(lines.join("\n"), TextStyle {
+                font: fonts.timer_font_handle.as_ref().expect("Fonts should be loaded").clone(),
+                font_size: 60.0,
+                ..default()
+            }),
+            text_anchor: Anchor::CenterLeft,
+            transform: Transform::from_translation(Vec3::new(720.0, 0.0, 101.0)),
+            ..default()
+        },
+        Popup
+    ));
+}
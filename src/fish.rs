@@ -3,12 +3,17 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 use rand::Rng;
-use rand::rngs::ThreadRng;
+use serde::Deserialize;
 
+use crate::chase::FishChase;
 use crate::constants::*;
-use crate::hook::HookedFish;
+use crate::hook::{FishEscaped, Hooked, HookedFish, LineSnapped, Reeling};
 use crate::physics::*;
 use crate::core::*;
+use crate::level::CurrentLevel;
+use crate::rng::*;
+use crate::species::*;
+use crate::tuning::Tuning;
 
 
 pub struct FishPlugin;
@@ -26,10 +31,13 @@ impl Plugin for FishPlugin {
             apply_fish_boundaries,
             apply_fish_animation,
             interpolate_returning_to_water_arcs,
-        ))
+            calm_spooked_fish,
+        ).run_if(in_level))
         .add_systems(PostUpdate, (
             handle_fish_returned_to_water,
             handle_fish_hooked,
+            handle_fish_line_snapped,
+            handle_fish_escaped,
             reset_fish
         ));
     }
@@ -44,6 +52,31 @@ pub struct FishReturnedToWater {
 #[derive(Event, Default)]
 pub struct FishLandedInWater;
 
+/// How a fish gets away when it's left on the hook too long, from the tuning file.
+#[derive(Deserialize, Clone, Debug)]
+pub struct EscapeTuning {
+    /// An escaping fish bolts off this many times faster than its usual burst.
+    pub dash_multiplier: f32,
+    /// How long an escaped fish ignores the hook.
+    pub spooked_time_s: f32,
+}
+
+impl EscapeTuning {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.dash_multiplier <= 0.0 || self.spooked_time_s < 0.0 {
+            errors.push(format!("escape needs a positive dash_multiplier and spooked_time_s, got {:?}", self));
+        }
+        errors
+    }
+}
+
+/// Just got off the hook, and won't bite or chase again until the timer runs out.
+#[derive(Component)]
+pub struct Spooked {
+    pub timer: Timer
+}
+
 #[derive(Component)]
 pub struct FishMovement {
     pub next_move_time: Timer,
@@ -66,7 +99,7 @@ pub struct FishLanePos {
     pub pos_y: f32
 }
 
-#[derive(Component)]
+#[derive(Component, Deserialize, Clone, Debug)]
 pub struct FishAnimation {
     pub base_scale: f32,
     pub max_scale_add_x: f32,
@@ -78,7 +111,9 @@ pub struct FishAnimation {
 
 #[derive(Component)]
 pub struct Fish {
-    pub size: usize
+    pub size: usize,
+    /// Index into `FishSpeciesList::species`.
+    pub species: usize
 }
 
 #[derive(Component, Debug)]
@@ -93,12 +128,16 @@ pub struct ReturningToWater {
     pub end_time_s: f32,
     pub gravity: f32,
     pub water_drag: f32,
+    /// Whether crossing `water_entrance_time_s` plays a splash, false for fish that are already in the water.
+    pub splashes: bool,
 }
 
 fn reset_fish(
     mut completed_events: EventReader<ResetLevel>,
     fish_query: Query<Entity, With<Fish>>,
     images: Res<ImageHandles>,
+    (current_level, tuning, species): (Res<CurrentLevel>, Res<Tuning>, Res<FishSpeciesList>),
+    mut rng: ResMut<GameRng>,
     mut commands: Commands,
 ) {
     if !completed_events.is_empty() {
@@ -106,12 +145,18 @@ fn reset_fish(
         for fish_entity in &fish_query {
             commands.entity(fish_entity).despawn();
         }
-        add_fish(images, commands);
+        //every level starts from its own seed so the layout can be reproduced
+        rng.reseed(current_level.level_seed());
+        add_fish(images, current_level, tuning, species, rng, commands);
     }
 }
 
 fn add_fish(
     images: Res<ImageHandles>,
+    current_level: Res<CurrentLevel>,
+    tuning: Res<Tuning>,
+    species: Res<FishSpeciesList>,
+    mut rng: ResMut<GameRng>,
     mut commands: Commands,
 ) {
     let fish_atlas_handle = images.fish_atlas_handle.as_ref().expect("Images should be loaded");
@@ -119,24 +164,28 @@ fn add_fish(
 
     let box_width = WATER_SIZE.x;
     let box_height = WATER_SIZE.y - height_offset;
-    let lane_height = box_height / FISH_PER_LEVEL as f32;
-    let mut rng = rand::thread_rng();
+    let level = current_level.definition();
+    let fish_count = level.fish_count();
+    let lane_height = box_height / fish_count as f32;
+    let fish_velocity = level.fish_velocity;
+    let rng = rng.as_mut();
     let mut fish_and_sort: Vec<(usize, usize)> = 
-        (0..FISH_PER_LEVEL)
-        .map(|f| (f, (rng.gen::<f32>() * FISH_PER_LEVEL as f32 * 10000.0) as usize))
+        level.fish.iter()
+        .map(|name| species.index_of(name).expect("level fish should be known species"))
+        .map(|species_index| (species_index, (rng.gen::<f32>() * fish_count as f32 * 10000.0) as usize))
         .collect();
     fish_and_sort.sort_by_key(|(_, key)| *key);
-    for (pos_index, fish_index) in fish_and_sort.iter().map(|(item, _)| item).enumerate() {
-        let fish_size = FISH_ATLAS_SIZES[*fish_index];
-        let fish_half_width = (fish_size - 1) as f32 * 20.0 + 30.0;
-        let pos_x = rand::random::<f32>() * box_width - (box_width / 2.0) + WATER_POS.x;
+    for (pos_index, species_index) in fish_and_sort.iter().map(|(item, _)| *item).enumerate() {
+        let fish_species = &species.species[species_index];
+        let fish_size = fish_species.size;
+        let fish_velocity = fish_velocity * fish_species.swim_speed;
+        let pos_x = rng.gen::<f32>() * box_width - (box_width / 2.0) + WATER_POS.x;
         let pos_y = WATER_POS.y - (box_height - height_offset) / 2.0 - height_offset / 2.0 + lane_height * pos_index as f32 + rng.gen::<f32>() * lane_height * 0.8;
-        let (mouth_pos, mouth_size) = FISH_MOUTH_POSITIONS_AND_SIZES[fish_size - 1];
         let x_scale = if rng.gen::<bool>() { 1.0 } else { -1.0 };
         commands.spawn((
             SpriteSheetBundle {
                 texture_atlas: fish_atlas_handle.clone(),
-                sprite: TextureAtlasSprite::new(*fish_index),
+                sprite: TextureAtlasSprite::new(species_index),
                 transform: Transform::from_translation(
                     Vec3::new(
                         pos_x, 
@@ -150,51 +199,45 @@ fn add_fish(
                 ..default()
             },
             FishMovement {
-                next_move_time: build_fish_movement_timer(&mut rng),
-                vel_to_apply: FISH_VELOCITY * x_scale
+                next_move_time: build_fish_movement_timer(rng, fish_species.burst_interval_s),
+                vel_to_apply: fish_velocity * x_scale
             },
             FishBoundaries {
-                min_x: -WATER_SIZE.x / 2.0 + WATER_POS.x + fish_half_width,
-                max_x: WATER_SIZE.x / 2.0 + WATER_POS.x - fish_half_width,
-            },
-            FishAnimation {
-                base_scale: 1.0,
-                max_scale_add_x: 0.3,
-                max_scale_add_y: 0.3,
-                charge_anim_time_s: 0.3,
-                dash_anim_time_s: 0.2,
-                reset_anim_time_s: 2.0,
+                min_x: -WATER_SIZE.x / 2.0 + WATER_POS.x + fish_species.half_width,
+                max_x: WATER_SIZE.x / 2.0 + WATER_POS.x - fish_species.half_width,
             },
+            fish_species.animation.clone(),
             FishLanePos {
                 pos_y
             },
             Fish {
                 size: fish_size,
+                species: species_index
             },
             Velocity {
-                x: ((FISH_VELOCITY / 2.0) + rng.gen::<f32>() * FISH_VELOCITY / 2.0) * x_scale,
+                x: ((fish_velocity / 2.0) + rng.gen::<f32>() * fish_velocity / 2.0) * x_scale,
                 y: 0.0,
-                drag_x: WATER_DRAG_X,
-                drag_y: WATER_DRAG_Y
+                drag_x: tuning.water_drag_x,
+                drag_y: tuning.water_drag_y
             }
         )).with_children(|parent| {
             parent.spawn((
-                SpatialBundle::from_transform(Transform::from_translation(mouth_pos.extend(0.0))),
-                FishMouth { mouth_size }
+                SpatialBundle::from_transform(Transform::from_translation(fish_species.mouth_offset.extend(0.0))),
+                FishMouth { mouth_size: fish_species.mouth_radius }
             ));
         });
     }
 }
 
 
-fn build_fish_movement_timer(rng: &mut ThreadRng) -> Timer {
-    let mut timer = Timer::from_seconds(rng.gen::<f32>() * 6.0 + 3.0, TimerMode::Repeating);
-    timer.tick(Duration::from_secs_f32(rng.gen::<f32>() * 9.0));
+fn build_fish_movement_timer(rng: &mut GameRng, (min_interval_s, max_interval_s): (f32, f32)) -> Timer {
+    let mut timer = Timer::from_seconds(rng.gen::<f32>() * (max_interval_s - min_interval_s) + min_interval_s, TimerMode::Repeating);
+    timer.tick(Duration::from_secs_f32(rng.gen::<f32>() * max_interval_s));
     timer
 }
 
 fn apply_fish_movement(
-    mut query: Query<(&mut Velocity, &mut FishMovement)>,
+    mut query: Query<(&mut Velocity, &mut FishMovement), Without<FishChase>>,
     time: Res<Time>
 ) {
     for (mut velocity, mut movement) in &mut query {
@@ -325,7 +368,7 @@ fn interpolate_returning_to_water_arcs(
             transform.scale = Vec3::new(1.0, 1.0, 1.0);
         } else if time.elapsed_seconds() > returning.water_entrance_time_s {
             //if we just crossed the water this frame
-            if (time.elapsed_seconds() - time.delta_seconds()) < returning.water_entrance_time_s {
+            if returning.splashes && (time.elapsed_seconds() - time.delta_seconds()) < returning.water_entrance_time_s {
                 on_landed_in_water.send_default();
             }
             let elapsed = time.elapsed_seconds() - returning.water_entrance_time_s;
@@ -341,27 +384,76 @@ fn interpolate_returning_to_water_arcs(
     }
 }
 
+fn handle_fish_line_snapped(
+    mut on_snapped: EventReader<LineSnapped>,
+    fish_query: Query<(&Transform, &FishLanePos), With<Reeling>>,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    mut commands: Commands
+) {
+    for event in on_snapped.iter() {
+        if let Ok((transform, lane_pos)) = fish_query.get(event.fish_entity) {
+            commands.entity(event.fish_entity).remove::<Reeling>();
+            commands.entity(event.fish_entity).insert(calculate_sink_path(
+                transform.translation.truncate(),
+                lane_pos.pos_y,
+                tuning.gravity,
+                tuning.water_drag_y,
+                time.elapsed_seconds()));
+        }
+    }
+}
+
+//a fish that snaps the line is already in the water, so its return skips the arc and only sinks back to its lane
+fn calculate_sink_path(
+    start_pos: Vec2,
+    lane_y: f32,
+    gravity_y: f32,
+    water_drag_y: f32,
+    start_time_s: f32
+) -> ReturningToWater {
+    debug_assert_ne!(0.0, water_drag_y);
+    let end_pos = Vec2::new(start_pos.x, lane_y.min(start_pos.y));
+    let time_to_lane = (2.0 / water_drag_y * (start_pos.y - end_pos.y)).sqrt();
+    let start_vel = Vec2::new(0.0, -water_drag_y * time_to_lane);
+    ReturningToWater {
+        start_vel,
+        water_entrance_vel: start_vel,
+        start_pos,
+        water_entrance_pos: start_pos,
+        end_pos,
+        start_time_s,
+        water_entrance_time_s: start_time_s,
+        end_time_s: start_time_s + time_to_lane,
+        gravity: gravity_y,
+        water_drag: water_drag_y,
+        splashes: false
+    }
+}
+
 fn handle_fish_returned_to_water(
     mut on_returned: EventReader<FishReturnedToWater>,
     images: Res<ImageHandles>,
-    fish_query: Query<Entity, With<Fish>>,
+    fish_query: Query<(Entity, &Fish)>,
+    (current_level, tuning, species): (Res<CurrentLevel>, Res<Tuning>, Res<FishSpeciesList>),
+    mut rng: ResMut<GameRng>,
     mut commands: Commands,
 ) {
     for event in on_returned.iter() {
-        for fish_entity in &fish_query {
+        for (fish_entity, fish) in &fish_query {
             if fish_entity == event.fish_entity {   
-                let mut rng = rand::thread_rng();
+                let fish_species = &species.species[fish.species];
                 commands.entity(event.fish_entity).remove::<(ReturningToWater, Handle<TextureAtlas>)>();
                 commands.entity(event.fish_entity).insert((
                     Velocity { 
                         x: event.end_vel.x,
                         y: event.end_vel.y,
-                        drag_x: WATER_DRAG_X,
-                        drag_y: WATER_DRAG_Y
+                        drag_x: tuning.water_drag_x,
+                        drag_y: tuning.water_drag_y
                     },
                     FishMovement {
-                        next_move_time: build_fish_movement_timer(&mut rng),
-                        vel_to_apply: FISH_VELOCITY
+                        next_move_time: build_fish_movement_timer(&mut rng, fish_species.burst_interval_s),
+                        vel_to_apply: current_level.definition().fish_velocity * fish_species.swim_speed
                     },
                     images.fish_atlas_handle.as_ref().expect("Images should be loaded").clone()
                 ));
@@ -370,6 +462,55 @@ fn handle_fish_returned_to_water(
     }
 }
 
+//the fish turns tail and bolts, then swims back to its lane like it would after a chase
+fn handle_fish_escaped(
+    mut on_escape: EventReader<FishEscaped>,
+    mut fish_query: Query<(&mut Transform, &Fish), With<Hooked>>,
+    (current_level, tuning, species): (Res<CurrentLevel>, Res<Tuning>, Res<FishSpeciesList>),
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands
+) {
+    for event in on_escape.iter() {
+        if let Ok((mut transform, fish)) = fish_query.get_mut(event.fish_entity) {
+            let fish_species = &species.species[fish.species];
+            let fish_velocity = current_level.definition().fish_velocity * fish_species.swim_speed;
+            transform.scale.x *= -1.0;
+            let facing = transform.scale.x.signum();
+            commands.entity(event.fish_entity).remove::<Hooked>();
+            commands.entity(event.fish_entity).insert((
+                Velocity {
+                    x: fish_velocity * tuning.escape.dash_multiplier * facing,
+                    y: 0.0,
+                    drag_x: tuning.water_drag_x,
+                    drag_y: tuning.water_drag_y
+                },
+                FishMovement {
+                    next_move_time: build_fish_movement_timer(&mut rng, fish_species.burst_interval_s),
+                    vel_to_apply: fish_velocity * facing
+                },
+                FishChase::ReturningToLane,
+                //its mouth is still at the hook, it would bite again straight away otherwise
+                Spooked {
+                    timer: Timer::from_seconds(tuning.escape.spooked_time_s, TimerMode::Once)
+                }
+            ));
+        }
+    }
+}
+
+fn calm_spooked_fish(
+    mut spooked_query: Query<(Entity, &mut Spooked)>,
+    time: Res<Time>,
+    mut commands: Commands
+) {
+    for (entity, mut spooked) in &mut spooked_query {
+        spooked.timer.tick(time.delta());
+        if spooked.timer.finished() {
+            commands.entity(entity).remove::<Spooked>();
+        }
+    }
+}
+
 fn handle_fish_hooked(
     mut on_hooked: EventReader<HookedFish>,
     mut fish_query: Query<(Entity, &mut Transform), With<Fish>>
@@ -0,0 +1,103 @@
+use bevy::{prelude::*, sprite::Anchor};
+use serde::{Deserialize, Serialize};
+use crate::{core::*, daily::DailyRun, level::CurrentLevel, storage};
+
+pub struct HighScoresPlugin;
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(storage::load::<HighScores>(HIGH_SCORES_KEY).unwrap_or_default())
+        .add_systems(OnEnter(GameState::Won), record_high_score);
+    }
+}
+
+const HIGH_SCORES_KEY: &str = "high_scores";
+pub const MAX_HIGH_SCORES_PER_MODE: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct HighScoreEntry {
+    pub time_s: f32,
+    pub mode: GameMode,
+    /// The run's `CurrentLevel::run_seed`, which replays every level of it.
+    pub seed: u64
+}
+
+/// Best win times, fastest first, kept separately for each `GameMode`.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct HighScores {
+    pub entries: Vec<HighScoreEntry>
+}
+
+impl HighScores {
+    pub fn for_mode(&self, mode: GameMode) -> impl Iterator<Item = &HighScoreEntry> {
+        self.entries.iter().filter(move |entry| entry.mode == mode)
+    }
+
+    /// Adds the entry if it's fast enough for its mode's table, returning its 1-based rank.
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        let rank = self.for_mode(entry.mode)
+            .filter(|existing| existing.time_s <= entry.time_s)
+            .count();
+        if rank >= MAX_HIGH_SCORES_PER_MODE {
+            return None;
+        }
+        self.entries.push(entry);
+        self.entries.sort_by(|a, b| a.time_s.total_cmp(&b.time_s));
+        let mut kept_per_mode = Vec::<(GameMode, usize)>::new();
+        self.entries.retain(|existing| {
+            match kept_per_mode.iter_mut().find(|(mode, _)| *mode == existing.mode) {
+                Some((_, kept)) => {
+                    *kept += 1;
+                    *kept <= MAX_HIGH_SCORES_PER_MODE
+                },
+                None => {
+                    kept_per_mode.push((existing.mode, 1));
+                    true
+                }
+            }
+        });
+        Some(rank + 1)
+    }
+}
+
+fn record_high_score(
+    game_timer: Res<GameTimer>,
+    mode: Res<GameMode>,
+    (current_level, daily_run): (Res<CurrentLevel>, Res<DailyRun>),
+    mut high_scores: ResMut<HighScores>,
+    fonts: Res<FontHandles>,
+    mut commands: Commands
+) {
+    //a campaign is only ranked by its total, once the last level is won
+    if !current_level.is_last() {
+        return;
+    }
+    //only the official daily attempt is ranked, practice runs after it don't count
+    if *mode == GameMode::Daily && !daily_run.is_official {
+        return;
+    }
+    let rank = high_scores.insert(HighScoreEntry {
+        time_s: current_level.previous_levels_time_s + game_timer.total_time_s,
+        mode: *mode,
+        seed: current_level.run_seed
+    });
+    storage::save(HIGH_SCORES_KEY, &*high_scores);
+    let rank_string = match rank {
+        Some(1) => "NEW BEST!".to_string(),
+        Some(rank) => format!("RANK #{}", rank),
+        None => return,
+    };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(rank_string, TextStyle {
+                font: fonts.timer_font_handle.as_ref().expect("Fonts should be loaded").clone(),
+                font_size: 100.0,
+                ..default()
+            }),
+            text_anchor: Anchor::Center,
+            transform: Transform::from_translation(Vec3::new(0.0, -410.0, 101.0)),
+            ..default()
+        },
+        Popup
+    ));
+}
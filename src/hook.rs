@@ -1,10 +1,20 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, sprite::Anchor};
+use serde::Deserialize;
+use crate::catch_grade::CatchGrade;
 use crate::constants::*;
+use crate::controls::{Action, ActionAxes};
+use crate::core::GameState;
 use crate::core::ImageHandles;
-use crate::core::PopupTimer;
 use crate::core::ResetLevel;
+use crate::core::in_level;
+use crate::level::CurrentLevel;
+use crate::chase::FishChase;
 use crate::fish::*;
 use crate::physics::*;
+use crate::pointer::Pointer;
+use crate::species::FishSpeciesList;
+use crate::tackle::Tackle;
+use crate::tuning::Tuning;
 
 pub struct HookPlugin;
 impl Plugin for HookPlugin {
@@ -15,21 +25,33 @@ impl Plugin for HookPlugin {
         .add_event::<HookedFish>()
         .add_event::<HookLandedInWater>()
         .add_event::<HookCast>()
-        .add_systems(Startup, add_hook)
+        .add_event::<LineSnapped>()
+        .add_event::<FishEscaped>()
+        .add_systems(Startup, (
+            add_hook,
+            add_cast_meter
+        ))
         .add_systems(Update, (
-            reel_in,
             interpolate_casting_arc,
-            move_hook,
-            cast_hook,
             fish_bite_hook,
             turn_hook_pink,
-            catch_fish,
             update_hook_while_waiting,
-        ))
+            update_cast_meter,
+        ).run_if(in_level))
+        .add_systems(Update, (
+            move_hook,
+            reel_in,
+            charge_cast,
+            cast_hook,
+            catch_fish,
+            let_fish_escape,
+        ).run_if(in_state(GameState::Playing)))
         .add_systems(PostUpdate, (
             handle_fish_reeled_to_surface,
+            handle_hook_line_snapped,
             handle_fish_on_bite,
             handle_hook_on_bite,
+            handle_hook_on_escape,
             handle_hook_landed_in_water,
             handle_hook_caught_fish,
             handle_fish_caught,
@@ -43,6 +65,12 @@ pub struct ReeledToSurface {
     pub entity: Entity
 }
 
+/// Reeling pulled too hard against the fish, it gets away and the hook comes back empty.
+#[derive(Event)]
+pub struct LineSnapped {
+    pub fish_entity: Entity
+}
+
 #[derive(Event)]
 pub struct HookLandedInWater {
     pub hook_entity: Entity
@@ -54,21 +82,31 @@ pub struct HookedFish {
     pub fish_entity: Entity,
 }
 
+/// The player waited too long after a bite and the fish spat out the hook.
+#[derive(Event)]
+pub struct FishEscaped {
+    pub hook_entity: Entity,
+    pub fish_entity: Entity,
+}
+
 #[derive(Event)]
 pub struct HookCast {
-    pub hook_entity: Entity
+    pub hook_entity: Entity,
+    /// Where on the water's surface the hook will land.
+    pub target: Vec2
 }
 
 #[derive(Event)]
 pub struct FishCaught {
     pub fish_entity: Entity,
     pub hook_entity: Entity,
-    pub is_critical: bool
+    pub grade: CatchGrade
 }
 
 #[derive(Component)]
 pub struct Hook {
-    pub move_speed: f32
+    pub move_speed: f32,
+    pub drift_speed: f32
 }
 
 #[derive(Component)]
@@ -90,36 +128,81 @@ pub struct CastingHook {
 }
 
 #[derive(Component, Debug)]
-pub struct HookInWater;
+pub struct HookInWater {
+    /// Where the line meets the surface, starts where the cast landed and follows the hook's drift.
+    pub surface_x: f32
+}
+
+/// Added while Space or the pointer is held before a cast.
+#[derive(Component, Debug)]
+pub struct CastCharge {
+    pub charge: f32,
+    /// Charged by dragging rather than by holding Space, it's released the same way.
+    pub by_pointer: bool
+}
+
+#[derive(Component)]
+pub struct CastMeterFill;
 
 #[derive(Component, Debug)]
 pub struct WaitingToBeCast;
 
+/// On a fish being reeled in. Tension builds while reeling and eases while
+/// the fish is given line, from 0 (slack) to 1 (snapped).
 #[derive(Component, Debug)]
-pub struct Reeling;
+pub struct Reeling {
+    pub tension: f32
+}
 
 #[derive(Component)]
 pub struct LineStartPoint;
 
+/// How reeling pulls on the line, from the tuning file.
+#[derive(Deserialize, Clone, Debug)]
+pub struct ReelTuning {
+    pub reel_speed: f32,
+    /// Tension gained per second of reeling the biggest species, smaller fish scale down from this.
+    pub tension_rise_rate: f32,
+    pub tension_ease_rate: f32,
+    /// Above this the line starts to show the strain, at 1.0 it snaps.
+    pub tension_warning: f32,
+    /// How fast the biggest species drags the line back down while the player isn't reeling.
+    pub fish_pull_speed: f32,
+}
+
+impl ReelTuning {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.reel_speed <= 0.0 || self.tension_rise_rate <= 0.0 || self.tension_ease_rate < 0.0 || self.fish_pull_speed < 0.0 {
+            errors.push(format!("reeling needs positive speeds and rates, got {:?}", self));
+        }
+        if !(0.0..1.0).contains(&self.tension_warning) {
+            errors.push(format!("reeling.tension_warning must be from 0 up to 1, got {}", self.tension_warning));
+        }
+        errors
+    }
+}
+
 fn catch_fish(
-    input: Res<Input<KeyCode>>,
+    (actions, pointer): (Res<Input<Action>>, Res<Pointer>),
     time: Res<Time>,
+    (current_level, tuning): (Res<CurrentLevel>, Res<Tuning>),
     fish_query: Query<(Entity, &Hooked)>,
     hook_query: Query<Entity, (With<Hook>, With<NearFish>)>,
-    popup_query: Query<(), With<PopupTimer>>,
     mut on_catch: EventWriter<FishCaught>,
     mut on_critical: EventWriter<ReeledToSurface>
 ) {
-    if popup_query.is_empty() {
-        if let Ok(hook_entity) = hook_query.get_single() {
-            if let Ok((fish_entity, hooked)) = fish_query.get_single() {
-                if input.just_pressed(KeyCode::Space) {
-                    let react_time = time.elapsed_seconds() - hooked.hook_time_s;
-                    let is_critical = react_time < CRITICAL_TIME;
-                    on_catch.send(FishCaught { fish_entity, hook_entity, is_critical });
-                    if react_time < CRITICAL_TIME {
-                        on_critical.send(ReeledToSurface { entity: fish_entity });
-                    }
+    if let Ok(hook_entity) = hook_query.get_single() {
+        if let Ok((fish_entity, hooked)) = fish_query.get_single() {
+            if actions.just_pressed(Action::Cast) || pointer.just_pressed {
+                let react_time = time.elapsed_seconds() - hooked.hook_time_s;
+                let grade = CatchGrade::from_reaction_time(
+                    react_time,
+                    current_level.definition().critical_time,
+                    &tuning.catch_windows);
+                on_catch.send(FishCaught { fish_entity, hook_entity, grade });
+                if grade.is_critical() {
+                    on_critical.send(ReeledToSurface { entity: fish_entity });
                 }
             }
         }
@@ -144,7 +227,8 @@ fn add_hook(
     commands.spawn((
         SpatialBundle::from_transform(Transform::from_translation(Vec3::ZERO)),
         Hook {
-            move_speed: 300.0
+            move_speed: 300.0,
+            drift_speed: 150.0
         },
         WaitingToBeCast,
     )).with_children(|parent| {
@@ -162,6 +246,41 @@ fn add_hook(
     });
 }
 
+//the meter runs along the water's surface, so the end of the fill is where the hook will land
+fn add_cast_meter(mut commands: Commands) {
+    let meter_size = Vec2::new(CAST_FAR_X - CAST_NEAR_X, 16.0);
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.6),
+                custom_size: Some(meter_size),
+                anchor: Anchor::CenterLeft,
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(CAST_NEAR_X, CAST_SURFACE_Y + 60.0, 20.0))
+                .with_scale(Vec3::new(0.0, 1.0, 1.0)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        CastMeterFill
+    ));
+}
+
+fn update_cast_meter(
+    hook_query: Query<Option<&CastCharge>, With<Hook>>,
+    mut meter_query: Query<(&mut Transform, &mut Visibility), With<CastMeterFill>>
+) {
+    if let Ok((mut meter_transform, mut meter_visibility)) = meter_query.get_single_mut() {
+        match hook_query.get_single().ok().flatten() {
+            Some(cast_charge) => {
+                meter_transform.scale.x = cast_charge.charge;
+                *meter_visibility = Visibility::Inherited;
+            },
+            None => *meter_visibility = Visibility::Hidden,
+        }
+    }
+}
+
 fn turn_hook_pink(
     mut hooks: Query<(&mut Sprite, Option<&NearFish>), With<Hook>>
 ) {
@@ -174,33 +293,71 @@ fn turn_hook_pink(
     }
 }
 
+//the cast button charges over time, a drag charges with how far the pointer has been pulled
+fn charge_cast(
+    mut hook_query: Query<(Entity, Option<&mut CastCharge>), With<WaitingToBeCast>>,
+    actions: Res<Input<Action>>,
+    pointer: Res<Pointer>,
+    time: Res<Time>,
+    mut commands: Commands
+) {
+    for (entity, cast_charge) in &mut hook_query {
+        match cast_charge {
+            Some(mut cast_charge) if cast_charge.by_pointer => {
+                cast_charge.charge = (pointer.drag().length() / CAST_DRAG_LENGTH).min(1.0);
+            },
+            Some(mut cast_charge) => {
+                cast_charge.charge = (cast_charge.charge + time.delta_seconds() / CAST_CHARGE_TIME_S).min(1.0);
+            },
+            None => {
+                if actions.just_pressed(Action::Cast) {
+                    commands.entity(entity).insert(CastCharge { charge: 0.0, by_pointer: false });
+                } else if pointer.just_pressed {
+                    commands.entity(entity).insert(CastCharge { charge: 0.0, by_pointer: true });
+                }
+            }
+        }
+    }
+}
+
+fn cast_target(charge: f32) -> Vec2 {
+    Vec2::new(CAST_NEAR_X + (CAST_FAR_X - CAST_NEAR_X) * charge, CAST_SURFACE_Y)
+}
+
 fn cast_hook(
-    hook_query: Query<Entity, (With<Hook>, With<WaitingToBeCast>)>,
+    hook_query: Query<(Entity, &CastCharge), With<WaitingToBeCast>>,
     line_start_query: Query<&GlobalTransform, With<LineStartPoint>>,
-    popup_query: Query<(), With<PopupTimer>>,
-    input: Res<Input<KeyCode>>,
+    (actions, pointer): (Res<Input<Action>>, Res<Pointer>),
     mut on_cast: EventWriter<HookCast>,
     time: Res<Time>,
+    tuning: Res<Tuning>,
     mut commands: Commands
 ) {
-    for entity in &hook_query {
-        if popup_query.is_empty() && input.just_pressed(KeyCode::Space) {
-            on_cast.send(HookCast{ hook_entity: entity });
-            commands.entity(entity).remove::<WaitingToBeCast>();
+    for (entity, cast_charge) in &hook_query {
+        let released =
+            if cast_charge.by_pointer {
+                pointer.just_released
+            } else {
+                actions.just_released(Action::Cast)
+            };
+        if released {
+            let target = cast_target(cast_charge.charge);
+            on_cast.send(HookCast{ hook_entity: entity, target });
+            commands.entity(entity).remove::<(WaitingToBeCast, CastCharge)>();
             if let Ok(line_start_pos) = line_start_query.get_single().map(|x| x.translation()) {
                 let (initial_vel, arc_time) = calculate_time_and_initial_vel_for_arc(
                     line_start_pos.x,
                     line_start_pos.y,
-                    CAST_TARGET_POS.x,
-                    CAST_TARGET_POS.y,
-                    GRAVITY,
-                    900.0 //TODO: K: make constant
+                    target.x,
+                    target.y,
+                    tuning.gravity,
+                    CAST_NEAR_APEX + (CAST_FAR_APEX - CAST_NEAR_APEX) * cast_charge.charge
                 );
                 commands.entity(entity).insert(CastingHook {
                     start_vel: initial_vel,
-                    gravity: GRAVITY,
+                    gravity: tuning.gravity,
                     start_pos: line_start_pos.truncate(),
-                    end_pos: CAST_TARGET_POS,
+                    end_pos: target,
                     start_time_s: time.elapsed_seconds(),
                     end_time_s: time.elapsed_seconds() + arc_time,
                 });
@@ -210,39 +367,62 @@ fn cast_hook(
 }
 
 fn move_hook(
-    mut query: Query<(&mut Transform, &Hook), (With<HookInWater>, Without<NearFish>)>,
-    popup_query: Query<(), With<PopupTimer>>,
-    input: Res<Input<KeyCode>>,
+    mut query: Query<(&mut Transform, &Hook, &mut HookInWater), Without<NearFish>>,
+    action_axes: Res<ActionAxes>,
+    pointer: Res<Pointer>,
+    (tackle, tuning): (Res<Tackle>, Res<Tuning>),
     time: Res<Time>
 ) {
-    if popup_query.is_empty() {
-
-        let up_pressed = input.pressed(KeyCode::W) || input.pressed(KeyCode::Up);
-        let down_pressed = input.pressed(KeyCode::S) || input.pressed(KeyCode::Down);
-        for (mut transform, hook) in &mut query {
-            let y_vel = (if up_pressed { 1.0 } else { 0.0 } + if down_pressed { -1.0 } else { 0.0 });
-            let y_del = y_vel * time.delta_seconds();
-            let new_y = transform.translation.y + y_del * hook.move_speed;
-            let water_top = WATER_POS.y + WATER_SIZE.y / 2.0 - 100.0;
-            let water_bottom = WATER_POS.y - WATER_SIZE.y / 2.0;
-            transform.translation.y = new_y.clamp(water_bottom, water_top);
+    let pointer_target = pointer.position.filter(|_| pointer.pressed);
+    for (mut transform, hook, mut in_water) in &mut query {
+        let mut y_vel = action_axes.hook_movement.y;
+        let mut x_vel = action_axes.hook_movement.x;
+        //holding the pointer drags the hook towards it, slowing down as it gets there so it doesn't overshoot
+        if let Some(target) = pointer_target {
+            let to_target = target - transform.translation.truncate();
+            y_vel = (to_target.y / (hook.move_speed * time.delta_seconds()).max(f32::EPSILON)).clamp(-1.0, 1.0);
+            x_vel = (to_target.x / (hook.drift_speed * time.delta_seconds()).max(f32::EPSILON)).clamp(-1.0, 1.0);
         }
+        if y_vel < 0.0 {
+            y_vel *= tackle.hook.sink_speed_multiplier(&tuning.hooks);
+        }
+        let y_del = y_vel * time.delta_seconds();
+        let new_y = transform.translation.y + y_del * hook.move_speed;
+        let water_top = WATER_POS.y + WATER_SIZE.y / 2.0 - 100.0;
+        let water_bottom = WATER_POS.y - WATER_SIZE.y / 2.0;
+        transform.translation.y = new_y.clamp(water_bottom, water_top);
+
+        //drifting sideways is slower, so it's for lining up on a fish rather than chasing it
+        let x_del = x_vel * time.delta_seconds();
+        let new_x = transform.translation.x + x_del * hook.drift_speed;
+        let water_left = WATER_POS.x - WATER_SIZE.x / 2.0 + 50.0;
+        let water_right = WATER_POS.x + WATER_SIZE.x / 2.0 - 50.0;
+        transform.translation.x = new_x.clamp(water_left, water_right);
+        in_water.surface_x = transform.translation.x;
     }
 }
 
+/// Fish that could take the hook, escaped fish stay away for a while.
+type BitingFishFilter = (Without<Hooked>, Without<Spooked>);
+
 fn fish_bite_hook(
-    fish_query: Query<(Entity, &Children), Without<Hooked>>,
+    fish_query: Query<(Entity, &Children, &Fish), BitingFishFilter>,
     fish_mouth_query: Query<(Entity, &GlobalTransform, &FishMouth)>,
     hook_query: Query<(Entity, &Transform), (With<HookInWater>, Without<NearFish>)>,
+    (tackle, tuning): (Res<Tackle>, Res<Tuning>),
+    species: Res<FishSpeciesList>,
     mut on_hook: EventWriter<HookedFish>,
 ) {
     for (hook_entity, hook) in &hook_query {
-        for (fish_entity, children) in &fish_query {
+        for (fish_entity, children, fish) in &fish_query {
+            if !tackle.will_bite(&species.species[fish.species]) {
+                continue;
+            }
             for (mouth_entity, mouth_pos, mouth) in &fish_mouth_query {
                 //children should contain exactly one item, the mouth
                 if mouth_entity == children[0] {
                     let distance = (hook.translation - mouth_pos.translation()).length();
-                    if distance < mouth.mouth_size {
+                    if distance < mouth.mouth_size + tackle.hook.bite_radius_bonus(&tuning.hooks) {
                         on_hook.send(HookedFish { hook_entity, fish_entity });
                         break;
                     }
@@ -252,16 +432,35 @@ fn fish_bite_hook(
     }
 }
 
+//reeling pulls the fish up but bigger fish strain the line more, reeling gently with the
+//trigger strains it less. letting go eases the tension while the fish drags itself back towards its lane
 fn reel_in(
-    mut reelable_query: Query<(Entity, &mut Transform), With<Reeling>>,
-    time: Res<Time>,
-    mut on_reeled: EventWriter<ReeledToSurface>
+    mut reelable_query: Query<(Entity, &mut Transform, &mut Reeling, &Fish, &FishLanePos)>,
+    (action_axes, pointer): (Res<ActionAxes>, Res<Pointer>),
+    (time, species, tuning): (Res<Time>, Res<FishSpeciesList>, Res<Tuning>),
+    mut on_reeled: EventWriter<ReeledToSurface>,
+    mut on_snapped: EventWriter<LineSnapped>
 ) {
-    let reel_speed = 600.0;
     let upper_boundary = WATER_POS.y + WATER_SIZE.y / 2.0;
-    for (entity, mut pos) in &mut reelable_query {
+    let reel = if pointer.pressed { 1.0 } else { action_axes.reel };
+    //the biggest species pulls at full strength
+    let max_size = species.max_size() as f32;
+    let reeling_tuning = &tuning.reeling;
+    for (entity, mut pos, mut reeling, fish, lane_pos) in &mut reelable_query {
+        let pull = fish.size as f32 / max_size;
+        if reel <= 0.0 {
+            reeling.tension = (reeling.tension - reeling_tuning.tension_ease_rate * time.delta_seconds()).max(0.0);
+            let new_y = pos.translation.y - pull * reeling_tuning.fish_pull_speed * time.delta_seconds();
+            pos.translation.y = new_y.max(lane_pos.pos_y.min(pos.translation.y));
+            continue;
+        }
+        reeling.tension += reel * pull * reeling_tuning.tension_rise_rate * time.delta_seconds();
+        if reeling.tension >= 1.0 {
+            on_snapped.send(LineSnapped { fish_entity: entity });
+            continue;
+        }
         let mut hit_surface = false;
-        let mut new_y = pos.translation.y + reel_speed * time.delta_seconds();
+        let mut new_y = pos.translation.y + reel * reeling_tuning.reel_speed * time.delta_seconds();
         if new_y > upper_boundary {
             hit_surface = true;
             new_y = upper_boundary;
@@ -287,6 +486,55 @@ fn handle_fish_reeled_to_surface(
     }
 }
 
+fn handle_hook_line_snapped(
+    mut on_snapped: EventReader<LineSnapped>,
+    mut hook_query: Query<(Entity, &mut Visibility), With<Hook>>,
+    mut commands: Commands
+) {
+    if !on_snapped.is_empty() {
+        on_snapped.clear();
+        if let Ok((hook_entity, mut visibility)) = hook_query.get_single_mut() {
+            *visibility = Visibility::Inherited;
+            commands.entity(hook_entity).insert(WaitingToBeCast);
+        }
+    }
+}
+
+//a catch on the same frame wins over the escape
+fn let_fish_escape(
+    (actions, pointer): (Res<Input<Action>>, Res<Pointer>),
+    time: Res<Time>,
+    species: Res<FishSpeciesList>,
+    fish_query: Query<(Entity, &Hooked, &Fish)>,
+    hook_query: Query<Entity, (With<Hook>, With<NearFish>)>,
+    mut on_escape: EventWriter<FishEscaped>
+) {
+    if actions.just_pressed(Action::Cast) || pointer.just_pressed {
+        return;
+    }
+    if let Ok(hook_entity) = hook_query.get_single() {
+        for (fish_entity, hooked, fish) in &fish_query {
+            let hooked_for_s = time.elapsed_seconds() - hooked.hook_time_s;
+            if hooked_for_s >= species.species[fish.species].escape_time_s {
+                on_escape.send(FishEscaped { hook_entity, fish_entity });
+            }
+        }
+    }
+}
+
+fn handle_hook_on_escape(
+    mut on_escape: EventReader<FishEscaped>,
+    mut hook_query: Query<&mut Visibility, With<Hook>>,
+    mut commands: Commands
+) {
+    for event in on_escape.iter() {
+        if let Ok(mut visibility) = hook_query.get_single_mut() {
+            *visibility = Visibility::Inherited;
+        }
+        commands.entity(event.hook_entity).remove::<NearFish>();
+    }
+}
+
 fn handle_hook_on_bite(
     mut on_hook: EventReader<HookedFish>,
     mut hook_query: Query<&mut Visibility, With<Hook>>,
@@ -307,7 +555,7 @@ fn handle_fish_on_bite(
 ) {
     for event in on_hook.iter() {
         commands.entity(event.fish_entity).insert(Hooked { hook_time_s: time.elapsed_seconds() });
-        commands.entity(event.fish_entity).remove::<(Velocity, FishMovement)>();
+        commands.entity(event.fish_entity).remove::<(Velocity, FishMovement, FishChase)>();
     }
 }
 
@@ -331,14 +579,14 @@ fn interpolate_casting_arc(
 
 fn handle_hook_landed_in_water(
     mut on_landed: EventReader<HookLandedInWater>,
-    hook_query: Query<Entity, With<CastingHook>>,
+    hook_query: Query<(Entity, &CastingHook)>,
     mut commands: Commands
 ) {
     for event in on_landed.iter() {
-        if let Ok(hook_entity) = hook_query.get_single() {
+        if let Ok((hook_entity, casting)) = hook_query.get_single() {
             if hook_entity == event.hook_entity {
                 commands.entity(hook_entity).remove::<CastingHook>();
-                commands.entity(hook_entity).insert(HookInWater);
+                commands.entity(hook_entity).insert(HookInWater { surface_x: casting.end_pos.x });
             }
         }
     }
@@ -359,26 +607,28 @@ fn handle_fish_caught(
 ) {
     for event in on_caught.iter() {
         commands.entity(event.fish_entity).remove::<(Hooked, FishMovement, Velocity)>();
-        if !event.is_critical {
-            commands.entity(event.fish_entity).insert(Reeling);
+        if !event.grade.is_critical() {
+            commands.entity(event.fish_entity).insert(Reeling { tension: 0.0 });
         }
     }
 }
 
 fn handle_hook_level_reset(
     mut on_reset: EventReader<ResetLevel>,
-    mut hook_query: Query<Entity, With<Hook>>,
+    mut hook_query: Query<(Entity, &mut Visibility), With<Hook>>,
     mut commands: Commands,
 ) {
     if !on_reset.is_empty() {
         on_reset.clear();
-        if let Ok(hook_entity) = hook_query.get_single_mut() {
+        if let Ok((hook_entity, mut visibility)) = hook_query.get_single_mut() {
+            //a restart from the pause menu can happen while the hook is hidden by a bite
+            *visibility = Visibility::Inherited;
             let mut commands = commands.entity(hook_entity);
             commands.remove::<(
                 NearFish,
                 HookInWater,
                 CastingHook,
-                Reeling
+                CastCharge
             )>();
             commands.insert(WaitingToBeCast);
         }
@@ -0,0 +1,150 @@
+use bevy::{prelude::*, sprite::Anchor};
+use crate::{
+    catch_stack::{handle_fish_landed_in_stack, CatchStack, FishKnockedOutOfStack, FishLandedInStack},
+    constants::*,
+    core::{in_level, FontHandles, GameTimer, ImageHandles, ASSETS_LOADED},
+    fish::Fish,
+    level::CurrentLevel,
+    snail::Snail
+};
+
+/// The timer, the time left and what's on the stack and still in the water, in the top right.
+pub struct HudPlugin;
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(ASSETS_LOADED, add_hud)
+        .add_systems(Update, update_hud_timer.run_if(in_level))
+        //after the landing is applied, or the preview would be a fish behind
+        .add_systems(PostUpdate, update_hud_fish
+            .after(handle_fish_landed_in_stack)
+            .run_if(
+                on_event::<FishLandedInStack>()
+                .or_else(on_event::<FishKnockedOutOfStack>())
+                .or_else(stacks_added)
+                .or_else(hud_added)));
+    }
+}
+
+const HUD_POS: Vec2 = Vec2::new(1320.0, 990.0);
+const HUD_FONT_SIZE: f32 = 60.0;
+const PREVIEW_BOTTOM: Vec2 = Vec2::new(1220.0, 480.0);
+const PREVIEW_SCALE: f32 = 0.2;
+const PREVIEW_SPACING: f32 = 30.0;
+/// Levels with more than one stack get a column each, going left.
+const PREVIEW_COLUMN_SPACING: f32 = 140.0;
+
+#[derive(Component)]
+struct HudTimer;
+
+#[derive(Component)]
+struct HudInWater;
+
+/// Parent of the little stack sprites, which are rebuilt whenever the stack changes.
+#[derive(Component)]
+struct HudStackPreview;
+
+fn hud_text(text: String, fonts: &FontHandles, position: Vec2) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::from_section(text, TextStyle {
+            font: fonts.timer_font_handle.as_ref().expect("Fonts should be loaded").clone(),
+            font_size: HUD_FONT_SIZE,
+            color: Color::BLACK,
+        }),
+        text_anchor: Anchor::TopRight,
+        transform: Transform::from_translation(position.extend(50.0)),
+        ..default()
+    }
+}
+
+fn add_hud(
+    fonts: Res<FontHandles>,
+    mut commands: Commands
+) {
+    commands.spawn((hud_text(String::new(), &fonts, HUD_POS), HudTimer));
+    commands.spawn((
+        hud_text(String::new(), &fonts, HUD_POS - Vec2::new(0.0, HUD_FONT_SIZE * 2.2)),
+        HudInWater
+    ));
+    commands.spawn((SpatialBundle::from_transform(Transform::from_translation(PREVIEW_BOTTOM.extend(50.0))), HudStackPreview));
+}
+
+//the first level was spawned before the hud, so there's no event to fill it in
+fn hud_added(preview_query: Query<(), Added<HudStackPreview>>) -> bool {
+    !preview_query.is_empty()
+}
+
+//a reset respawns the stacks and the fish, so wait for the new ones rather than the event
+fn stacks_added(stack_query: Query<(), Added<CatchStack>>) -> bool {
+    !stack_query.is_empty()
+}
+
+//the clock is the one part of the hud with no event behind it, so it's worked out every
+//frame but the text is only touched when the tenth of a second it shows goes by.
+//the snail moves at a steady pace, so how far it has left is how much time is left
+fn update_hud_timer(
+    game_timer: Res<GameTimer>,
+    current_level: Res<CurrentLevel>,
+    snail_query: Query<&Transform, With<Snail>>,
+    mut timer_query: Query<&mut Text, With<HudTimer>>
+) {
+    let remaining_s = snail_query.get_single().map_or(0.0, |snail_pos| {
+        let progress = (snail_pos.translation.x - SNAIL_START_POS.x) / (SNAIL_END_POS.x - SNAIL_START_POS.x);
+        current_level.definition().length_s * (1.0 - progress.clamp(0.0, 1.0))
+    });
+    let shown = format!("TIME {:.1}\nLEFT {:.1}", game_timer.total_time_s, remaining_s);
+    for mut text in &mut timer_query {
+        if text.sections[0].value != shown {
+            text.sections[0].value = shown.clone();
+        }
+    }
+}
+
+fn update_hud_fish(
+    stack_query: Query<&CatchStack>,
+    fish_query: Query<(Entity, &Fish)>,
+    preview_query: Query<Entity, With<HudStackPreview>>,
+    mut in_water_query: Query<&mut Text, With<HudInWater>>,
+    images: Res<ImageHandles>,
+    mut commands: Commands
+) {
+    let mut stacks: Vec<&CatchStack> = stack_query.iter().collect();
+    stacks.sort_by_key(|stack| stack.index);
+    let stacked: Vec<Entity> = stacks.iter().flat_map(|stack| stack.entities()).collect();
+
+    let mut in_water: Vec<usize> = fish_query.iter()
+        .filter(|(entity, _)| !stacked.contains(entity))
+        .map(|(_, fish)| fish.size)
+        .collect();
+    in_water.sort_unstable();
+    let in_water: Vec<String> = in_water.iter().map(|size| size.to_string()).collect();
+    for mut text in &mut in_water_query {
+        text.sections[0].value = if in_water.is_empty() {
+            "ALL STACKED".to_string()
+        } else {
+            format!("IN WATER {}", in_water.join(" "))
+        };
+    }
+
+    let Ok(preview) = preview_query.get_single() else {
+        return;
+    };
+    commands.entity(preview).despawn_descendants();
+    let atlas = images.stack_atlas_handle.as_ref().expect("Images should be loaded");
+    for (column, stack) in stacks.iter().enumerate() {
+        let column_x = -(column as f32) * PREVIEW_COLUMN_SPACING;
+        for (index, entity) in stack.entities().enumerate() {
+            let Ok((_, fish)) = fish_query.get(entity) else {
+                continue;
+            };
+            let sprite = commands.spawn(SpriteSheetBundle {
+                texture_atlas: atlas.clone(),
+                sprite: TextureAtlasSprite::new(fish.species),
+                transform: Transform::from_translation(Vec3::new(column_x, index as f32 * PREVIEW_SPACING, index as f32 * 0.1))
+                    .with_scale(Vec3::splat(PREVIEW_SCALE)),
+                ..default()
+            }).id();
+            commands.entity(preview).add_child(sprite);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::{constants::*, core::*, daily::DailyRun, rng::{GameRng, SeedConfig}, stack_rules::StackRules, tuning::Tuning};
+
+pub struct LevelPlugin;
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(PreStartup, add_current_level)
+        .add_systems(OnExit(GameState::Title), start_run);
+    }
+}
+
+/// Everything that changes from one level to the next, read from the tuning file.
+#[derive(Deserialize, Clone, Debug)]
+pub struct LevelDefinition {
+    /// One fish is spawned per entry, named after its species in `assets/fish.species.ron`.
+    pub fish: Vec<String>,
+    pub length_s: f32,
+    pub fish_velocity: f32,
+    pub critical_time: f32,
+    /// Where the catch stacks go and the rule each is built under. The player switches
+    /// between them, and the level is won once every stack is finished.
+    #[serde(default = "default_stacks")]
+    pub stacks: Vec<StackDefinition>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct StackDefinition {
+    /// Where the bottom of the stack sits, `STACK_POS` for a level with one stack.
+    pub position: Vec2,
+    /// What landing on the stack knocks off, and when it counts as finished.
+    #[serde(default)]
+    pub rule: StackRules,
+}
+
+fn default_stacks() -> Vec<StackDefinition> {
+    vec![StackDefinition { position: STACK_POS.truncate(), rule: StackRules::default() }]
+}
+
+impl LevelDefinition {
+    pub fn fish_count(&self) -> usize {
+        self.fish.len()
+    }
+
+    pub fn snail_speed(&self) -> f32 {
+        (SNAIL_END_POS.x - SNAIL_START_POS.x) / self.length_s
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.fish.is_empty() {
+            errors.push("fish needs at least one fish".to_string());
+        }
+        if self.length_s <= 0.0 {
+            errors.push(format!("length_s must be positive, got {}", self.length_s));
+        }
+        if self.fish_velocity <= 0.0 {
+            errors.push(format!("fish_velocity must be positive, got {}", self.fish_velocity));
+        }
+        if self.critical_time < 0.0 {
+            errors.push(format!("critical_time can't be negative, got {}", self.critical_time));
+        }
+        if self.stacks.is_empty() {
+            errors.push("stacks needs at least one stack".to_string());
+        }
+        errors
+    }
+}
+
+pub fn levels_for(mode: GameMode, tuning: &Tuning) -> &[LevelDefinition] {
+    match mode {
+        GameMode::Classic | GameMode::Daily => std::slice::from_ref(&tuning.classic_level),
+        GameMode::Campaign => &tuning.campaign_levels,
+    }
+}
+
+/// The level being played, and the time banked from earlier levels of the same run.
+#[derive(Resource)]
+pub struct CurrentLevel {
+    pub mode: GameMode,
+    pub index: usize,
+    pub levels: Vec<LevelDefinition>,
+    pub previous_levels_time_s: f32,
+    /// Drawn once per run, every level's seed comes from it so the whole run can be replayed.
+    pub run_seed: u64,
+}
+
+impl CurrentLevel {
+    pub fn new(mode: GameMode, tuning: &Tuning, run_seed: u64) -> CurrentLevel {
+        CurrentLevel {
+            mode,
+            index: 0,
+            levels: levels_for(mode, tuning).to_vec(),
+            previous_levels_time_s: 0.0,
+            run_seed,
+        }
+    }
+
+    /// The first level plays on the run seed itself, later ones step away from it.
+    pub fn level_seed(&self) -> u64 {
+        self.run_seed.wrapping_add((self.index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+
+    pub fn definition(&self) -> &LevelDefinition {
+        &self.levels[self.index]
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.index + 1 >= self.levels.len()
+    }
+
+    /// Moves on to the next level after a win, banking the time it took.
+    pub fn advance(&mut self, level_time_s: f32) {
+        debug_assert!(!self.is_last());
+        self.index += 1;
+        self.previous_levels_time_s += level_time_s;
+    }
+
+    /// Goes back to the first level once a run is finished, starting a new run from `run_seed`.
+    pub fn restart(&mut self, run_seed: u64) {
+        self.index = 0;
+        self.previous_levels_time_s = 0.0;
+        self.run_seed = run_seed;
+    }
+
+    /// Picks up edited tuning, staying on the same level if it still exists.
+    pub fn refresh(&mut self, tuning: &Tuning) {
+        self.levels = levels_for(self.mode, tuning).to_vec();
+        self.index = self.index.min(self.levels.len() - 1);
+    }
+}
+
+fn add_current_level(
+    mode: Res<GameMode>,
+    tuning: Res<Tuning>,
+    (seed_config, mut daily_run): (Res<SeedConfig>, ResMut<DailyRun>),
+    mut commands: Commands
+) {
+    let current_level = CurrentLevel::new(*mode, &tuning, seed_config.new_run_seed(*mode, &mut daily_run));
+    commands.insert_resource(GameRng::new(current_level.level_seed()));
+    commands.insert_resource(current_level);
+}
+
+//leaving the title always starts a fresh run of the chosen mode, rebuilding
+//the level behind the menu so it uses that mode's first level and seed
+fn start_run(
+    mode: Res<GameMode>,
+    tuning: Res<Tuning>,
+    (seed_config, mut daily_run): (Res<SeedConfig>, ResMut<DailyRun>),
+    mut current_level: ResMut<CurrentLevel>,
+    mut on_reset: EventWriter<ResetLevel>
+) {
+    *current_level = CurrentLevel::new(*mode, &tuning, seed_config.new_run_seed(*mode, &mut daily_run));
+    on_reset.send_default();
+}
@@ -0,0 +1,155 @@
+use bevy::{prelude::*, asset::{HandleId, LoadState}, sprite::Anchor};
+use crate::{audio::AudioHandles, constants::background_color, core::*};
+
+pub struct LoadingPlugin;
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(LoadFailures::default())
+        .add_systems(OnEnter(GameState::Loading), show_loading_screen)
+        .add_systems(Update, update_loading.run_if(in_state(GameState::Loading)))
+        .add_systems(OnExit(GameState::Loading), despawn_loading_screen)
+        .add_systems(OnEnter(GameState::LoadFailed), show_load_failed_screen);
+    }
+}
+
+const LOADING_BAR_SIZE: Vec2 = Vec2::new(1200.0, 60.0);
+
+/// Paths of the assets that couldn't be loaded, shown on the error screen.
+#[derive(Resource, Default)]
+pub struct LoadFailures {
+    pub paths: Vec<String>
+}
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct LoadingBarFill;
+
+//the timer font is one of the things being loaded, so these screens use bevy's built in font
+fn show_loading_screen(mut commands: Commands) {
+    commands.spawn((
+        SpatialBundle::from_transform(Transform::from_translation(Vec3::new(0.0, 0.0, 200.0))),
+        LoadingScreen
+    )).with_children(|parent| {
+        parent.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: background_color(),
+                custom_size: Some(Vec2::new(4000.0, 3000.0)),
+                ..default()
+            },
+            ..default()
+        });
+        parent.spawn(Text2dBundle {
+            text: Text::from_section("LOADING", TextStyle {
+                font_size: 100.0,
+                color: Color::BLACK,
+                ..default()
+            }),
+            text_anchor: Anchor::Center,
+            transform: Transform::from_translation(Vec3::new(0.0, 120.0, 1.0)),
+            ..default()
+        });
+        parent.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.3),
+                custom_size: Some(LOADING_BAR_SIZE),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 1.0)),
+            ..default()
+        });
+        parent.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::BLACK,
+                    custom_size: Some(LOADING_BAR_SIZE),
+                    anchor: Anchor::CenterLeft,
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(-LOADING_BAR_SIZE.x / 2.0, 0.0, 2.0))
+                    .with_scale(Vec3::new(0.0, 1.0, 1.0)),
+                ..default()
+            },
+            LoadingBarFill
+        ));
+    });
+}
+
+fn update_loading(
+    images: Res<ImageHandles>,
+    fonts: Res<FontHandles>,
+    audio: Res<AudioHandles>,
+    asset_server: Res<AssetServer>,
+    mut fill_query: Query<&mut Transform, With<LoadingBarFill>>,
+    mut load_failures: ResMut<LoadFailures>,
+    mut next_state: ResMut<NextState<GameState>>
+) {
+    let ids: Vec<HandleId> = images.ids().into_iter()
+        .chain(fonts.ids())
+        .chain(audio.ids())
+        .collect();
+    let mut loaded = 0;
+    load_failures.paths.clear();
+    for id in ids.iter() {
+        match asset_server.get_load_state(*id) {
+            LoadState::Loaded => loaded += 1,
+            LoadState::Failed => load_failures.paths.push(
+                asset_server.get_handle_path(*id)
+                    .map_or(format!("{:?}", id), |path| path.path().display().to_string())),
+            _ => {}
+        }
+    }
+    if !load_failures.paths.is_empty() {
+        println!("failed to load: {}", load_failures.paths.join(", "));
+        next_state.set(GameState::LoadFailed);
+        return;
+    }
+    for mut fill_transform in &mut fill_query {
+        fill_transform.scale.x = loaded as f32 / ids.len().max(1) as f32;
+    }
+    if loaded == ids.len() {
+        next_state.set(GameState::Title);
+    }
+}
+
+fn despawn_loading_screen(
+    screen_query: Query<Entity, With<LoadingScreen>>,
+    mut commands: Commands
+) {
+    for entity in &screen_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn show_load_failed_screen(
+    load_failures: Res<LoadFailures>,
+    mut commands: Commands
+) {
+    let mut lines = vec!["FAILED TO LOAD".to_string(), String::new()];
+    lines.extend(load_failures.paths.iter().cloned());
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: background_color(),
+                custom_size: Some(Vec2::new(4000.0, 3000.0)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 200.0)),
+            ..default()
+        },
+        LoadingScreen
+    )).with_children(|parent| {
+        parent.spawn(Text2dBundle {
+            text: Text::from_section(lines.join("\n"), TextStyle {
+                font_size: 60.0,
+                color: Color::BLACK,
+                ..default()
+            }).with_alignment(TextAlignment::Center),
+            text_anchor: Anchor::Center,
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 1.0)),
+            ..default()
+        });
+    });
+}
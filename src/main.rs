@@ -2,14 +2,34 @@
 
 mod audio;
 mod bear;
+mod catch_grade;
 mod catch_stack;
+mod chase;
 mod clouds;
 mod constants;
+mod controls;
 mod core;
+mod daily;
 mod fish;
+mod high_scores;
 mod hook;
+mod hud;
+mod level;
+mod loading;
+mod menu;
+mod pause;
 mod physics;
+mod pointer;
+mod rng;
+mod score;
+mod settings;
 mod snail;
+mod species;
+mod stack_rules;
+mod storage;
+mod tackle;
+mod title;
+mod tuning;
 mod window;
 
 use bevy::prelude::*;
@@ -18,13 +38,31 @@ use bevy::diagnostic::{LogDiagnosticsPlugin, FrameTimeDiagnosticsPlugin};
 
 use audio::*;
 use bear::*;
+use catch_grade::*;
 use catch_stack::*;
+use chase::*;
 use clouds::*;
+use controls::*;
 use core::*;
+use daily::*;
 use fish::*;
+use high_scores::*;
 use hook::*;
+use hud::*;
+use level::*;
+use loading::*;
+use menu::*;
+use pause::*;
 use physics::*;
+use pointer::*;
+use rng::*;
+use score::*;
+use settings::*;
 use snail::*;
+use species::*;
+use tackle::*;
+use title::*;
+use tuning::*;
 use window::*;
 
 fn main() {
@@ -36,17 +74,34 @@ fn main() {
         FrameTimeDiagnosticsPlugin::default(),
         HSLWindowPlugin,
         CorePlugin,
+        TuningPlugin,
+        SpeciesPlugin,
+        RngPlugin,
+        LevelPlugin,
         HSLAudioPlugin,
         SnailPlugin,
         CloudsPlugin,
         PhysicsPlugin,
         FishPlugin,
         HookPlugin,
-        CatchStackPlugin,
-        BearPlugin
+        CatchStackPlugin
     ))
-    .add_systems(Update, (
-        bevy::window::close_on_esc,
+    .add_plugins((
+        BearPlugin,
+        CatchGradePlugin,
+        LoadingPlugin,
+        ChasePlugin,
+        ControlsPlugin,
+        MenuPlugin,
+        HighScoresPlugin,
+        HudPlugin,
+        DailyPlugin,
+        PausePlugin,
+        PointerPlugin,
+        ScorePlugin,
+        SettingsPlugin,
+        TacklePlugin,
+        TitlePlugin
     ))
     .run();
 }
@@ -0,0 +1,213 @@
+use bevy::{prelude::*, sprite::Anchor};
+use crate::{controls::Action, pointer::Pointer};
+
+/// Shared navigation for the text menus (title, pause, ...). Only one `Menu`
+/// is expected to be on screen at a time; screens listen for `MenuItemChosen`
+/// and `MenuBack` and look up their own action component on the chosen item.
+pub struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<MenuItemChosen>()
+        .add_event::<MenuBack>()
+        .add_systems(Update, (
+            navigate_menu_with_actions,
+            navigate_menu_with_gamepad,
+            navigate_menu_with_pointer,
+            update_menu_colors,
+        ).chain());
+    }
+}
+
+#[derive(Event)]
+pub struct MenuItemChosen {
+    pub item_entity: Entity
+}
+
+#[derive(Event, Default)]
+pub struct MenuBack;
+
+#[derive(Component, Default)]
+pub struct Menu {
+    pub selected: usize,
+    pub item_count: usize
+}
+
+#[derive(Component)]
+pub struct MenuItem {
+    pub index: usize,
+    pub enabled: bool
+}
+
+const SELECTED_COLOR: Color = Color::WHITE;
+const UNSELECTED_COLOR: Color = Color::GRAY;
+const DISABLED_COLOR: Color = Color::rgba(0.3, 0.3, 0.3, 1.0);
+//clickable area around each item's center, in world units
+const MENU_ITEM_HALF_SIZE: Vec2 = Vec2::new(500.0, 70.0);
+const STICK_THRESHOLD: f32 = 0.5;
+
+pub fn spawn_menu_item(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: &str,
+    index: usize,
+    enabled: bool,
+    position: Vec3,
+    extra: impl Bundle
+) {
+    parent.spawn((
+        Text2dBundle {
+            text: Text::from_section(label, TextStyle {
+                font: font.clone(),
+                font_size: 120.0,
+                color: if enabled { UNSELECTED_COLOR } else { DISABLED_COLOR },
+            }),
+            text_anchor: Anchor::Center,
+            transform: Transform::from_translation(position),
+            ..default()
+        },
+        MenuItem { index, enabled },
+        extra
+    ));
+}
+
+/// Finds the next enabled item in the given direction, wrapping around.
+fn step_selection(selected: usize, step: isize, enabled: &[bool]) -> usize {
+    let count = enabled.len() as isize;
+    let mut index = selected as isize;
+    for _ in 0..count {
+        index = (index + step).rem_euclid(count);
+        if enabled[index as usize] {
+            return index as usize;
+        }
+    }
+    selected
+}
+
+fn enabled_items(item_query: &Query<(Entity, &MenuItem, &GlobalTransform)>, item_count: usize) -> Vec<bool> {
+    let mut enabled = vec![false; item_count];
+    for (_, item, _) in item_query {
+        if item.index < item_count {
+            enabled[item.index] = item.enabled;
+        }
+    }
+    enabled
+}
+
+fn selected_item(item_query: &Query<(Entity, &MenuItem, &GlobalTransform)>, selected: usize) -> Option<Entity> {
+    item_query.iter()
+        .find(|(_, item, _)| item.index == selected && item.enabled)
+        .map(|(entity, _, _)| entity)
+}
+
+//goes through the player's bindings, so the keys and buttons that move the hook
+//move around the menu too, wherever they've been rebound to
+fn navigate_menu_with_actions(
+    mut menu_query: Query<&mut Menu>,
+    item_query: Query<(Entity, &MenuItem, &GlobalTransform)>,
+    actions: Res<Input<Action>>,
+    mut on_chosen: EventWriter<MenuItemChosen>,
+    mut on_back: EventWriter<MenuBack>
+) {
+    if let Ok(mut menu) = menu_query.get_single_mut() {
+        let enabled = enabled_items(&item_query, menu.item_count);
+        if actions.just_pressed(Action::HookUp) {
+            menu.selected = step_selection(menu.selected, -1, &enabled);
+        }
+        if actions.just_pressed(Action::HookDown) {
+            menu.selected = step_selection(menu.selected, 1, &enabled);
+        }
+        if actions.just_pressed(Action::Cast) {
+            if let Some(item_entity) = selected_item(&item_query, menu.selected) {
+                on_chosen.send(MenuItemChosen { item_entity });
+            }
+        }
+        if actions.just_pressed(Action::Pause) {
+            on_back.send_default();
+        }
+    }
+}
+
+fn navigate_menu_with_gamepad(
+    mut menu_query: Query<&mut Menu>,
+    item_query: Query<(Entity, &MenuItem, &GlobalTransform)>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut stick_latched: Local<bool>,
+    mut on_back: EventWriter<MenuBack>
+) {
+    if let Ok(mut menu) = menu_query.get_single_mut() {
+        let enabled = enabled_items(&item_query, menu.item_count);
+        for gamepad in gamepads.iter() {
+            let stick_y = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY)).unwrap_or(0.0);
+            //the stick moves one item per push, it has to return to center before moving again
+            let stick_step =
+                if *stick_latched {
+                    if stick_y.abs() < STICK_THRESHOLD {
+                        *stick_latched = false;
+                    }
+                    0
+                } else if stick_y > STICK_THRESHOLD {
+                    *stick_latched = true;
+                    -1
+                } else if stick_y < -STICK_THRESHOLD {
+                    *stick_latched = true;
+                    1
+                } else {
+                    0
+                };
+            if stick_step != 0 {
+                menu.selected = step_selection(menu.selected, stick_step, &enabled);
+            }
+            if buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::East)) {
+                on_back.send_default();
+            }
+        }
+    }
+}
+
+fn navigate_menu_with_pointer(
+    mut menu_query: Query<&mut Menu>,
+    item_query: Query<(Entity, &MenuItem, &GlobalTransform)>,
+    pointer: Res<Pointer>,
+    mut on_cursor_moved: EventReader<CursorMoved>,
+    mut on_chosen: EventWriter<MenuItemChosen>
+) {
+    //only hover when the mouse actually moves, so a resting cursor doesn't fight the keys
+    let cursor_moved = !on_cursor_moved.is_empty();
+    on_cursor_moved.clear();
+    if let Ok(mut menu) = menu_query.get_single_mut() {
+        if let Some(pointer_pos) = pointer.position {
+            for (item_entity, item, item_pos) in &item_query {
+                let offset = (pointer_pos - item_pos.translation().truncate()).abs();
+                if item.enabled && offset.x < MENU_ITEM_HALF_SIZE.x && offset.y < MENU_ITEM_HALF_SIZE.y {
+                    if (cursor_moved || pointer.just_pressed) && menu.selected != item.index {
+                        menu.selected = item.index;
+                    }
+                    if pointer.just_pressed {
+                        on_chosen.send(MenuItemChosen { item_entity });
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn update_menu_colors(
+    menu_query: Query<&Menu, Changed<Menu>>,
+    mut item_query: Query<(&mut Text, &MenuItem)>
+) {
+    if let Ok(menu) = menu_query.get_single() {
+        for (mut text, item) in &mut item_query {
+            text.sections[0].style.color =
+                if !item.enabled {
+                    DISABLED_COLOR
+                } else if item.index == menu.selected {
+                    SELECTED_COLOR
+                } else {
+                    UNSELECTED_COLOR
+                };
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::{prelude::*, app::AppExit, sprite::Anchor};
+use crate::{controls::Action, core::*, constants::*, menu::*};
+
+pub struct PausePlugin;
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, (
+            pause_game.run_if(in_state(GameState::Playing)),
+            handle_pause_menu_chosen.run_if(in_state(GameState::Paused)),
+            handle_pause_menu_back.run_if(in_state(GameState::Paused)),
+        ))
+        .add_systems(OnEnter(GameState::Paused), (
+            freeze_time,
+            add_pause_menu,
+        ))
+        .add_systems(OnExit(GameState::Paused), (
+            unfreeze_time,
+            despawn_pause_menu,
+        ));
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMenuOption {
+    Resume,
+    Restart,
+    Quit
+}
+
+const PAUSE_MENU_OPTIONS: [(PauseMenuOption, &str); 3] = [
+    (PauseMenuOption::Resume, "RESUME"),
+    (PauseMenuOption::Restart, "RESTART"),
+    (PauseMenuOption::Quit, "QUIT"),
+];
+
+#[derive(Component)]
+pub struct PauseMenu;
+
+fn pause_game(
+    actions: Res<Input<Action>>,
+    mut next_state: ResMut<NextState<GameState>>
+) {
+    if actions.just_pressed(Action::Pause) {
+        next_state.set(GameState::Paused);
+    }
+}
+
+//everything in gameplay reads from Time, including the arcs that interpolate over
+//elapsed_seconds, so pausing the clock freezes all of it in place
+fn freeze_time(mut time: ResMut<Time>) {
+    time.pause();
+}
+
+fn unfreeze_time(mut time: ResMut<Time>) {
+    time.unpause();
+}
+
+fn add_pause_menu(
+    fonts: Res<FontHandles>,
+    mut commands: Commands
+) {
+    let font = fonts.timer_font_handle.as_ref().expect("Fonts should be loaded");
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.5),
+                custom_size: Some(BACKGROUND_SIZE),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 200.0)),
+            ..default()
+        },
+        Menu { selected: 0, item_count: PAUSE_MENU_OPTIONS.len() },
+        PauseMenu
+    )).with_children(|parent| {
+        parent.spawn(Text2dBundle {
+            text: Text::from_section("PAUSED", TextStyle {
+                font: font.clone(),
+                font_size: 200.0,
+                color: Color::WHITE,
+            }),
+            text_anchor: Anchor::Center,
+            transform: Transform::from_translation(Vec3::new(0.0, 300.0, 1.0)),
+            ..default()
+        });
+        for (index, (option, label)) in PAUSE_MENU_OPTIONS.iter().enumerate() {
+            spawn_menu_item(
+                parent,
+                font,
+                label,
+                index,
+                true,
+                Vec3::new(0.0, 50.0 - 170.0 * index as f32, 1.0),
+                *option
+            );
+        }
+    });
+}
+
+fn handle_pause_menu_chosen(
+    mut on_chosen: EventReader<MenuItemChosen>,
+    option_query: Query<&PauseMenuOption>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut on_reset: EventWriter<ResetLevel>,
+    mut on_exit: EventWriter<AppExit>
+) {
+    for event in on_chosen.iter() {
+        if let Ok(option) = option_query.get(event.item_entity) {
+            match option {
+                PauseMenuOption::Resume => {
+                    next_state.set(GameState::Playing);
+                },
+                PauseMenuOption::Restart => {
+                    on_reset.send_default();
+                    next_state.set(GameState::Playing);
+                },
+                PauseMenuOption::Quit => {
+                    on_exit.send(AppExit);
+                }
+            }
+        }
+    }
+}
+
+fn handle_pause_menu_back(
+    mut on_back: EventReader<MenuBack>,
+    mut next_state: ResMut<NextState<GameState>>
+) {
+    if !on_back.is_empty() {
+        on_back.clear();
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn despawn_pause_menu(
+    menu_query: Query<Entity, With<PauseMenu>>,
+    mut commands: Commands
+) {
+    for entity in &menu_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
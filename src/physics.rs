@@ -1,9 +1,10 @@
 use bevy::prelude::*;
+use crate::core::in_level;
 
 pub struct PhysicsPlugin;
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, apply_velocity);
+        app.add_systems(Update, apply_velocity.run_if(in_level));
     }
 }
 
@@ -0,0 +1,63 @@
+use bevy::{prelude::*, input::InputSystem, window::PrimaryWindow};
+
+pub struct PointerPlugin;
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(Pointer::default())
+        .add_systems(PreUpdate, update_pointer.after(InputSystem));
+    }
+}
+
+/// The mouse and touch screen folded into one pointer, so browsers on phones
+/// play the same as the mouse. The left button or the first finger down press it.
+#[derive(Resource, Default, Debug)]
+pub struct Pointer {
+    /// In world coordinates, `None` while the cursor is outside the window.
+    pub position: Option<Vec2>,
+    pub pressed: bool,
+    pub just_pressed: bool,
+    pub just_released: bool,
+    /// Where the current press started, in world coordinates.
+    pub press_start: Option<Vec2>,
+}
+
+impl Pointer {
+    /// How far the pointer has moved since the current press started.
+    pub fn drag(&self) -> Vec2 {
+        match (self.press_start, self.position) {
+            (Some(start), Some(position)) => position - start,
+            _ => Vec2::ZERO,
+        }
+    }
+}
+
+fn to_world_position(
+    camera_query: &Query<(&Camera, &GlobalTransform)>,
+    viewport_pos: Vec2
+) -> Option<Vec2> {
+    let (camera, camera_transform) = camera_query.get_single().ok()?;
+    camera.viewport_to_world_2d(camera_transform, viewport_pos)
+}
+
+fn update_pointer(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    mouse: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
+    mut pointer: ResMut<Pointer>
+) {
+    //a finger that just lifted still has to report where it was, so the release lands in the right place
+    let touch_pos = touches.iter().next()
+        .or_else(|| touches.iter_just_released().next())
+        .map(|touch| touch.position());
+    let cursor_pos = window_query.get_single().ok().and_then(|window| window.cursor_position());
+    pointer.position = touch_pos.or(cursor_pos)
+        .and_then(|viewport_pos| to_world_position(&camera_query, viewport_pos));
+    pointer.pressed = mouse.pressed(MouseButton::Left) || touches.iter().next().is_some();
+    pointer.just_pressed = mouse.just_pressed(MouseButton::Left) || touches.any_just_pressed();
+    pointer.just_released = !pointer.pressed && (mouse.just_released(MouseButton::Left) || touches.any_just_released());
+    if pointer.just_pressed {
+        pointer.press_start = pointer.position;
+    }
+}
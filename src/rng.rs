@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use crate::{core::GameMode, daily::{self, DailyRun}};
+
+pub struct RngPlugin;
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SeedConfig { fixed_seed: seed_from_args() });
+    }
+}
+
+/// The only source of gameplay randomness. It's reseeded from `CurrentLevel::level_seed`
+/// at the start of every level, so a seed always produces the same layout and fish behavior.
+#[derive(Resource)]
+pub struct GameRng {
+    rng: StdRng
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> GameRng {
+        GameRng {
+            rng: StdRng::seed_from_u64(seed)
+        }
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        *self = GameRng::new(seed);
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Where run seeds come from: a fixed seed from `--seed` or the title menu,
+/// otherwise a fresh random one every run.
+#[derive(Resource, Default)]
+pub struct SeedConfig {
+    pub fixed_seed: Option<u64>
+}
+
+impl SeedConfig {
+    /// The seed every level of a new run is derived from. A daily run is pinned to
+    /// today here, so one that goes past midnight keeps its seed and its day.
+    pub fn new_run_seed(&self, mode: GameMode, daily_run: &mut DailyRun) -> u64 {
+        match mode {
+            GameMode::Daily => {
+                daily_run.date = daily::today();
+                daily::daily_seed(daily_run.date)
+            },
+            _ => self.fixed_seed.unwrap_or_else(rand::random),
+        }
+    }
+}
+
+/// Reads `--seed <u64>` or `--seed=<u64>` from the command line.
+fn seed_from_args() -> Option<u64> {
+    let args: Vec<String> = std::env::args().collect();
+    for (index, arg) in args.iter().enumerate() {
+        let value =
+            if let Some(value) = arg.strip_prefix("--seed=") {
+                Some(value)
+            } else if arg == "--seed" {
+                args.get(index + 1).map(|value| value.as_str())
+            } else {
+                None
+            };
+        if let Some(value) = value {
+            match value.parse::<u64>() {
+                Ok(seed) => return Some(seed),
+                Err(_) => println!("ignoring invalid seed: {}", value),
+            }
+        }
+    }
+    None
+}
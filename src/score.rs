@@ -0,0 +1,269 @@
+use bevy::{prelude::*, sprite::Anchor, utils::HashMap};
+use serde::Deserialize;
+use crate::{
+    catch_grade::CatchGrade,
+    catch_stack::{FishKnockedOutOfStack, FishLandedInStack},
+    core::{FontHandles, GameState, Popup, ResetLevel, ASSETS_LOADED},
+    hook::FishCaught,
+    tuning::Tuning
+};
+
+pub struct ScorePlugin;
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(Score::default())
+        .add_systems(ASSETS_LOADED, add_score_label)
+        .add_systems(OnEnter(GameState::Won), show_score_breakdown)
+        .add_systems(PostUpdate, (
+            handle_score_on_catch,
+            handle_score_on_stack,
+            reset_score,
+            update_score_label.run_if(resource_changed::<Score>().or_else(resource_changed::<Tuning>())),
+        ));
+    }
+}
+
+/// What fish are worth and how streaks and knock outs change that, from the tuning file.
+#[derive(Deserialize, Clone, Debug)]
+pub struct ScoreTuning {
+    pub points_per_fish_size: i32,
+    /// Each critical catch in a row adds this much to the multiplier on landed fish.
+    pub streak_multiplier_step: f32,
+    pub max_streak_multiplier: f32,
+    /// Lost for every fish knocked off the stack, on top of the points it was worth.
+    pub knock_out_penalty: i32,
+}
+
+impl ScoreTuning {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.points_per_fish_size <= 0 || self.knock_out_penalty < 0 {
+            errors.push(format!("scoring needs positive points_per_fish_size and knock_out_penalty, got {:?}", self));
+        }
+        if self.streak_multiplier_step < 0.0 || self.max_streak_multiplier < 1.0 {
+            errors.push(format!("scoring streak multipliers can't lower the score, got {:?}", self));
+        }
+        errors
+    }
+}
+
+/// Points for the current level, kept by category so the win popup can break them down.
+#[derive(Resource, Default, Debug)]
+pub struct Score {
+    /// `ScoreTuning::points_per_fish_size` for every size of fish on the stack.
+    pub fish_points: i32,
+    /// Extra from the `CatchGrade` each fish on the stack was caught with.
+    pub catch_bonus: i32,
+    /// Extra from the critical streak each fish on the stack landed during.
+    pub streak_bonus: i32,
+    pub penalties: i32,
+    /// Critical catches in a row without knocking a fish off the stack.
+    pub streak: u32,
+    pub best_streak: u32,
+    pub knock_outs: u32,
+    //the grade a fish was caught with, until it lands
+    grades: HashMap<Entity, CatchGrade>,
+    //what each fish on the stack is worth, taken back if it's knocked off
+    stacked: HashMap<Entity, FishScore>,
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+struct FishScore {
+    fish_points: i32,
+    catch_bonus: i32,
+    streak_bonus: i32,
+}
+
+impl Score {
+    pub fn total(&self) -> i32 {
+        self.fish_points + self.catch_bonus + self.streak_bonus - self.penalties
+    }
+
+    pub fn streak_multiplier(&self, scoring: &ScoreTuning) -> f32 {
+        (1.0 + self.streak as f32 * scoring.streak_multiplier_step).min(scoring.max_streak_multiplier)
+    }
+
+    //anything short of a critical catch ends the streak
+    fn add_catch(&mut self, entity: Entity, grade: CatchGrade) {
+        self.grades.insert(entity, grade);
+        if grade.is_critical() {
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+        } else {
+            self.streak = 0;
+        }
+    }
+
+    fn add_fish(&mut self, entity: Entity, fish_size: usize, scoring: &ScoreTuning) {
+        let grade = self.grades.remove(&entity).unwrap_or(CatchGrade::Late);
+        let fish_points = (fish_size as i32) * scoring.points_per_fish_size;
+        let graded_points = fish_points as f32 * grade.score_multiplier();
+        let fish_score = FishScore {
+            fish_points,
+            catch_bonus: graded_points.round() as i32 - fish_points,
+            streak_bonus: (graded_points * (self.streak_multiplier(scoring) - 1.0)).round() as i32,
+        };
+        self.fish_points += fish_score.fish_points;
+        self.catch_bonus += fish_score.catch_bonus;
+        self.streak_bonus += fish_score.streak_bonus;
+        self.stacked.insert(entity, fish_score);
+    }
+
+    //a fish that bounced off never scored, so it only costs the penalty
+    fn knock_out_fish(&mut self, entity: Entity, scoring: &ScoreTuning) {
+        let fish_score = self.stacked.remove(&entity).unwrap_or_default();
+        self.fish_points -= fish_score.fish_points;
+        self.catch_bonus -= fish_score.catch_bonus;
+        self.streak_bonus -= fish_score.streak_bonus;
+        self.penalties += scoring.knock_out_penalty;
+        self.knock_outs += 1;
+        self.streak = 0;
+    }
+}
+
+#[derive(Component)]
+struct ScoreLabel;
+
+fn handle_score_on_catch(
+    mut on_catch: EventReader<FishCaught>,
+    mut score: ResMut<Score>
+) {
+    for event in on_catch.iter() {
+        score.add_catch(event.fish_entity, event.grade);
+    }
+}
+
+//landings first, so a fish that lands and is knocked off in the same frame is taken back off
+fn handle_score_on_stack(
+    mut on_land: EventReader<FishLandedInStack>,
+    mut on_knocked_out: EventReader<FishKnockedOutOfStack>,
+    tuning: Res<Tuning>,
+    mut score: ResMut<Score>
+) {
+    for event in on_land.iter() {
+        score.add_fish(event.entity, event.fish_size, &tuning.scoring);
+    }
+    for event in on_knocked_out.iter() {
+        score.knock_out_fish(event.fish_entity, &tuning.scoring);
+    }
+}
+
+fn reset_score(
+    mut on_reset: EventReader<ResetLevel>,
+    mut score: ResMut<Score>
+) {
+    if !on_reset.is_empty() {
+        on_reset.clear();
+        *score = Score::default();
+    }
+}
+
+fn add_score_label(
+    fonts: Res<FontHandles>,
+    (score, tuning): (Res<Score>, Res<Tuning>),
+    mut commands: Commands
+) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(score_label_text(&score, &tuning.scoring), TextStyle {
+                font: fonts.timer_font_handle.as_ref().expect("Fonts should be loaded").clone(),
+                font_size: 60.0,
+                color: Color::BLACK,
+            }),
+            text_anchor: Anchor::BottomRight,
+            transform: Transform::from_translation(Vec3::new(1320.0, -990.0, 50.0)),
+            ..default()
+        },
+        ScoreLabel
+    ));
+}
+
+fn score_label_text(score: &Score, scoring: &ScoreTuning) -> String {
+    if score.streak > 1 {
+        format!("STREAK x{:.2}\nSCORE {}", score.streak_multiplier(scoring), score.total())
+    } else {
+        format!("SCORE {}", score.total())
+    }
+}
+
+fn update_score_label(
+    score: Res<Score>,
+    tuning: Res<Tuning>,
+    mut label_query: Query<&mut Text, With<ScoreLabel>>
+) {
+    for mut text in &mut label_query {
+        text.sections[0].value = score_label_text(&score, &tuning.scoring);
+    }
+}
+
+fn show_score_breakdown(
+    score: Res<Score>,
+    fonts: Res<FontHandles>,
+    mut commands: Commands
+) {
+    let lines = [
+        format!("SCORE {}", score.total()),
+        String::new(),
+        format!("FISH {}", score.fish_points),
+        format!("CATCHES +{}", score.catch_bonus),
+        format!("STREAKS +{}", score.streak_bonus),
+        format!("KNOCK OUTS -{} ({})", score.penalties, score.knock_outs),
+        format!("BEST STREAK {}", score.best_streak),
+    ];
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(lines.join("\n"), TextStyle {
+                font: fonts.timer_font_handle.as_ref().expect("Fonts should be loaded").clone(),
+                font_size: 60.0,
+                ..default()
+            }),
+            text_anchor: Anchor::CenterRight,
+            transform: Transform::from_translation(Vec3::new(-720.0, 0.0, 101.0)),
+            ..default()
+        },
+        Popup
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical_catches_build_a_streak_and_anything_else_ends_it() {
+        let mut score = Score::default();
+        let grades = [
+            (CatchGrade::Perfect, 1),
+            (CatchGrade::Perfect, 2),
+            (CatchGrade::Good, 0),
+            (CatchGrade::Perfect, 1),
+            (CatchGrade::Late, 0),
+            (CatchGrade::Great, 0),
+        ];
+        for (index, (grade, streak)) in grades.into_iter().enumerate() {
+            score.add_catch(Entity::from_raw(index as u32), grade);
+            assert_eq!(score.streak, streak, "after catch {} ({:?})", index, grade);
+        }
+        assert_eq!(score.best_streak, 2);
+    }
+
+    #[test]
+    fn knocking_a_fish_off_ends_the_streak() {
+        let scoring = ScoreTuning {
+            points_per_fish_size: 100,
+            streak_multiplier_step: 0.25,
+            max_streak_multiplier: 2.0,
+            knock_out_penalty: 50,
+        };
+        let mut score = Score::default();
+        let fish = Entity::from_raw(0);
+        score.add_catch(fish, CatchGrade::Perfect);
+        score.add_fish(fish, 3, &scoring);
+        score.add_catch(Entity::from_raw(1), CatchGrade::Perfect);
+        score.knock_out_fish(fish, &scoring);
+        assert_eq!(score.streak, 0);
+        assert_eq!(score.best_streak, 2);
+        assert_eq!(score.total(), -50);
+    }
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::{controls::Bindings, storage};
+
+pub struct SettingsPlugin;
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut settings = storage::load::<Settings>(SETTINGS_KEY).unwrap_or_default();
+        settings.bindings.fill_missing();
+        app.insert_resource(settings);
+    }
+}
+
+const SETTINGS_KEY: &str = "settings";
+
+/// Player preferences that outlive the session, changed from the title screen's settings page.
+#[derive(Resource, Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub bindings: Bindings,
+}
+
+impl Settings {
+    pub fn save(&self) {
+        storage::save(SETTINGS_KEY, self);
+    }
+}
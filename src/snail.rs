@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use crate::{core::*, constants::*, physics::Velocity, catch_stack::StackCompleted, hook::HookCast};
+use crate::{core::*, constants::*, physics::Velocity, catch_stack::StackCompleted, hook::HookCast, level::CurrentLevel};
 
 pub struct SnailPlugin;
 impl Plugin for SnailPlugin {
@@ -13,12 +13,14 @@ impl Plugin for SnailPlugin {
         .add_systems(Update, (
             start_snail,
             update_snail,
+        ).run_if(in_state(GameState::Playing)))
+        .add_systems(Update, (
             animate_snail,
             update_lifespan,
-        ))
+        ).run_if(in_level))
         .add_systems(PostUpdate, (
             handle_snail_on_reset,
-            create_snail_particles,
+            create_snail_particles.run_if(in_state(GameState::Playing)),
             handle_snail_on_stack_complete
         ));
     }
@@ -51,6 +53,7 @@ pub struct Stopped;
 
 fn add_snail(
     images: Res<ImageHandles>,
+    current_level: Res<CurrentLevel>,
     mut commands: Commands
 ) {
     commands.spawn((
@@ -62,7 +65,7 @@ fn add_snail(
                     SNAIL_START_POS.extend(0.0)),
             ..default()
         },
-        Snail { speed: SNAIL_SPEED },
+        Snail { speed: current_level.definition().snail_speed() },
         Stopped,
         ParticleTimer {
             timer: Timer::from_seconds(1.0, TimerMode::Repeating)
@@ -175,13 +178,15 @@ fn update_lifespan(
 
 fn handle_snail_on_reset(
     mut on_level_end: EventReader<ResetLevel>,
-    mut snail_query: Query<(Entity, &mut Transform), With<Snail>>,
+    mut snail_query: Query<(Entity, &mut Transform, &mut Snail)>,
+    current_level: Res<CurrentLevel>,
     mut commands: Commands
 ) {
     if !on_level_end.is_empty() {
         on_level_end.clear();
-        if let Ok((entity, mut snail_pos)) = snail_query.get_single_mut() {
+        if let Ok((entity, mut snail_pos, mut snail)) = snail_query.get_single_mut() {
             snail_pos.translation = SNAIL_START_POS.extend(0.0);
+            snail.speed = current_level.definition().snail_speed();
             commands.entity(entity).insert(Stopped);
         }
     }
@@ -0,0 +1,263 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture
+};
+use serde::Deserialize;
+use crate::{core::ImageHandles, fish::FishAnimation, tackle::Bait, tuning::Tuning};
+
+pub struct SpeciesPlugin;
+impl Plugin for SpeciesPlugin {
+    fn build(&self, app: &mut App) {
+        //built in for the same reason as the tuning, see `TuningPlugin`
+        let species = FishSpeciesList::parse(DEFAULT_SPECIES.as_bytes())
+            .expect("built in fish species should be valid");
+        app
+        .add_asset::<FishSpeciesList>()
+        .add_asset_loader(FishSpeciesLoader)
+        .insert_resource(species)
+        .insert_resource(FishSpeciesHandle::default())
+        .add_systems(PreStartup, (
+            load_species,
+            check_built_in_levels
+        ))
+        .add_systems(PreUpdate, apply_species);
+    }
+}
+
+const SPECIES_PATH: &str = "fish.species.ron";
+const DEFAULT_SPECIES: &str = include_str!("../assets/fish.species.ron");
+
+/// An image cut into regions by hand, since species don't have to line up on a grid.
+#[derive(Deserialize, Clone, Debug)]
+pub struct SpriteSheet {
+    pub path: String,
+    pub size: Vec2,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct FishSpecies {
+    /// What levels use to ask for this fish.
+    pub name: String,
+    /// Landing on the stack knocks off every fish with a smaller size.
+    pub size: usize,
+    pub fish_region: Rect,
+    pub stack_region: Rect,
+    /// How far from its center the fish turns around at the edge of the water.
+    pub half_width: f32,
+    pub mouth_offset: Vec2,
+    pub mouth_radius: f32,
+    pub stack_height: f32,
+    /// Multiplies the level's `fish_velocity`.
+    pub swim_speed: f32,
+    /// The shortest and longest wait between bursts of speed.
+    pub burst_interval_s: (f32, f32),
+    /// How long the fish stays on the hook before spitting it out.
+    pub escape_time_s: f32,
+    /// The baits this fish will bite, it ignores the hook otherwise.
+    pub baits: Vec<Bait>,
+    pub animation: FishAnimation,
+}
+
+/// Every fish that can be spawned, loaded from `assets/fish.species.ron`. A
+/// species' index in `species` is also its sprite index in both atlases.
+#[derive(Resource, Deserialize, TypeUuid, TypePath, Clone, Debug)]
+#[uuid = "2f8e4b1c-7a36-4d0e-b5c9-81e0d3a6f4b2"]
+pub struct FishSpeciesList {
+    pub fish_sheet: SpriteSheet,
+    pub stack_sheet: SpriteSheet,
+    pub species: Vec<FishSpecies>,
+}
+
+impl FishSpeciesList {
+    fn parse(bytes: &[u8]) -> Result<FishSpeciesList, String> {
+        let species: FishSpeciesList = ron::de::from_bytes(bytes).map_err(|err| err.to_string())?;
+        let errors = species.validate();
+        if errors.is_empty() {
+            Ok(species)
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    /// Everything wrong with the values, one message per problem.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.species.is_empty() {
+            errors.push("species needs at least one fish".to_string());
+        }
+        for (index, species) in self.species.iter().enumerate() {
+            let mut error = |message: String| errors.push(format!("species[{}] {}: {}", index, species.name, message));
+            if self.species[..index].iter().any(|other| other.name == species.name) {
+                error("name is used by an earlier species".to_string());
+            }
+            if species.size == 0 {
+                error("size must be at least 1".to_string());
+            }
+            for (region_name, region, sheet) in [
+                ("fish_region", species.fish_region, &self.fish_sheet),
+                ("stack_region", species.stack_region, &self.stack_sheet)
+            ] {
+                let sheet_rect = Rect::from_corners(Vec2::ZERO, sheet.size);
+                if region.is_empty() || !sheet_rect.contains(region.min) || !sheet_rect.contains(region.max) {
+                    error(format!("{} {:?} isn't inside {}", region_name, region, sheet.path));
+                }
+            }
+            if species.half_width <= 0.0 {
+                error(format!("half_width must be positive, got {}", species.half_width));
+            }
+            if species.mouth_radius <= 0.0 {
+                error(format!("mouth_radius must be positive, got {}", species.mouth_radius));
+            }
+            if species.stack_height <= 0.0 {
+                error(format!("stack_height must be positive, got {}", species.stack_height));
+            }
+            if species.swim_speed <= 0.0 {
+                error(format!("swim_speed must be positive, got {}", species.swim_speed));
+            }
+            if species.escape_time_s <= 0.0 {
+                error(format!("escape_time_s must be positive, got {}", species.escape_time_s));
+            }
+            if species.baits.is_empty() {
+                error("baits needs at least one bait".to_string());
+            }
+            let (min_interval_s, max_interval_s) = species.burst_interval_s;
+            if min_interval_s <= 0.0 || max_interval_s < min_interval_s {
+                error(format!("burst_interval_s must be positive and in order, got {:?}", species.burst_interval_s));
+            }
+        }
+        errors
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.species.iter().position(|species| species.name == name)
+    }
+
+    /// The biggest `FishSpecies::size`, which pulls on the line the hardest.
+    pub fn max_size(&self) -> usize {
+        self.species.iter().map(|species| species.size).max().unwrap_or(1)
+    }
+
+    /// Names asked for by the tuning's levels that aren't in this list.
+    pub fn check_levels(&self, tuning: &Tuning) -> Vec<String> {
+        let levels = std::iter::once(("classic_level".to_string(), &tuning.classic_level))
+            .chain(tuning.campaign_levels.iter()
+                .enumerate()
+                .map(|(index, level)| (format!("campaign_levels[{}]", index), level)));
+        let mut errors = Vec::new();
+        for (level_name, level) in levels {
+            for name in level.fish.iter() {
+                if self.index_of(name).is_none() {
+                    errors.push(format!("{}: unknown fish species {}", level_name, name));
+                }
+            }
+        }
+        errors
+    }
+
+    pub fn build_fish_atlas(&self, image: Handle<Image>) -> TextureAtlas {
+        build_atlas(image, &self.fish_sheet, self.species.iter().map(|species| species.fish_region))
+    }
+
+    pub fn build_stack_atlas(&self, image: Handle<Image>) -> TextureAtlas {
+        build_atlas(image, &self.stack_sheet, self.species.iter().map(|species| species.stack_region))
+    }
+}
+
+fn build_atlas(image: Handle<Image>, sheet: &SpriteSheet, regions: impl Iterator<Item = Rect>) -> TextureAtlas {
+    let mut atlas = TextureAtlas::new_empty(image, sheet.size);
+    for region in regions {
+        atlas.add_texture(region);
+    }
+    atlas
+}
+
+#[derive(Resource, Default)]
+struct FishSpeciesHandle(Handle<FishSpeciesList>);
+
+struct FishSpeciesLoader;
+impl AssetLoader for FishSpeciesLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let species = FishSpeciesList::parse(bytes).map_err(|err| {
+                println!("invalid fish species in {}:\n{}", load_context.path().display(), err);
+                bevy::asset::Error::msg(err)
+            })?;
+            load_context.set_default_asset(LoadedAsset::new(species));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["species.ron"]
+    }
+}
+
+fn load_species(
+    mut species_handle: ResMut<FishSpeciesHandle>,
+    asset_server: Res<AssetServer>
+) {
+    species_handle.0 = asset_server.load(SPECIES_PATH);
+}
+
+fn check_built_in_levels(
+    species: Res<FishSpeciesList>,
+    tuning: Res<Tuning>
+) {
+    let errors = species.check_levels(&tuning);
+    if !errors.is_empty() {
+        panic!("built in levels don't match the built in fish species:\n{}", errors.join("\n"));
+    }
+}
+
+//the fish in the water only hold an index into the list, so they pick up edited values
+//straight away. adding, removing or reordering species would point them at the wrong
+//species or past the end, so that needs a restart instead
+fn apply_species(
+    mut on_species_event: EventReader<AssetEvent<FishSpeciesList>>,
+    species_handle: Res<FishSpeciesHandle>,
+    species_assets: Res<Assets<FishSpeciesList>>,
+    tuning: Res<Tuning>,
+    mut species: ResMut<FishSpeciesList>,
+    (mut images, mut atlases): (ResMut<ImageHandles>, ResMut<Assets<TextureAtlas>>),
+    asset_server: Res<AssetServer>
+) {
+    for event in on_species_event.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            if *handle != species_handle.0 {
+                continue;
+            }
+            let Some(loaded) = species_assets.get(handle) else {
+                continue;
+            };
+            let errors = loaded.check_levels(&tuning);
+            if !errors.is_empty() {
+                println!("ignoring fish species from {}:\n{}", SPECIES_PATH, errors.join("\n"));
+                continue;
+            }
+            let same_species = loaded.species.len() == species.species.len()
+                && loaded.species.iter().zip(species.species.iter()).all(|(new, old)| new.name == old.name);
+            if !same_species {
+                println!("ignoring fish species from {}: species can't be added, removed or reordered while the game runs, restart to use them", SPECIES_PATH);
+                continue;
+            }
+            println!("loaded fish species from {}", SPECIES_PATH);
+            *species = loaded.clone();
+            let fish_handle: Handle<Image> = asset_server.load(species.fish_sheet.path.as_str());
+            let stack_handle: Handle<Image> = asset_server.load(species.stack_sheet.path.as_str());
+            if let Some(atlas_handle) = images.fish_atlas_handle.as_ref() {
+                atlases.set_untracked(atlas_handle, species.build_fish_atlas(fish_handle.clone()));
+            }
+            if let Some(atlas_handle) = images.stack_atlas_handle.as_ref() {
+                atlases.set_untracked(atlas_handle, species.build_stack_atlas(stack_handle.clone()));
+            }
+            images.fish_handle = Some(fish_handle);
+            images.stack_handle = Some(stack_handle);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use serde::Deserialize;
+
+/// Which rule a level's stack is built under, set per level in the tuning file.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum StackRules {
+    /// A landing fish knocks off every smaller fish, so the biggest go first.
+    #[default]
+    Descending,
+    /// A landing fish knocks off every bigger fish, so the smallest go first.
+    Ascending,
+    /// A fish can't sit on one of the same size, the one underneath is knocked off.
+    NoMatchingNeighbours,
+    /// Odd and even sizes have to alternate, the one underneath is knocked off otherwise.
+    AlternatingParity,
+    /// Like the towers of Hanoi, a fish bigger than the top of the stack bounces off.
+    Hanoi,
+}
+
+impl StackRules {
+    pub fn rule(&self) -> &'static dyn StackRule {
+        match self {
+            StackRules::Descending => &DescendingRule,
+            StackRules::Ascending => &AscendingRule,
+            StackRules::NoMatchingNeighbours => &NoMatchingNeighboursRule,
+            StackRules::AlternatingParity => &AlternatingParityRule,
+            StackRules::Hanoi => &HanoiRule,
+        }
+    }
+}
+
+/// What a fish landing on the stack does to it.
+#[derive(Default, Debug, PartialEq)]
+pub struct Landing {
+    /// Indices into the stack, bottom first, of the fish knocked off.
+    pub knocked_out: Vec<usize>,
+    /// The landed fish is thrown off and the stack is left alone.
+    pub bounced: bool,
+}
+
+/// Decides which fish stay on the stack and when it's finished. Sizes are
+/// `FishSpecies::size`, and stacks are listed bottom first.
+pub trait StackRule: Send + Sync {
+    fn land(&self, stack: &[usize], landed: usize) -> Landing;
+
+    /// `level_sizes` has every fish in the level, including the ones already on the stack.
+    fn is_complete(&self, stack: &[usize], level_sizes: &[usize]) -> bool {
+        stack.len() == level_sizes.len()
+    }
+}
+
+fn knock_out_where(stack: &[usize], knocks_out: impl Fn(usize) -> bool) -> Landing {
+    Landing {
+        knocked_out: (0..stack.len()).filter(|index| knocks_out(stack[*index])).collect(),
+        bounced: false,
+    }
+}
+
+//only the fish right underneath is checked, anything lower already passed
+fn knock_out_top_if(stack: &[usize], knocks_out: impl Fn(usize) -> bool) -> Landing {
+    Landing {
+        knocked_out: stack.last()
+            .filter(|top| knocks_out(**top))
+            .map(|_| vec![stack.len() - 1])
+            .unwrap_or_default(),
+        bounced: false,
+    }
+}
+
+struct DescendingRule;
+impl StackRule for DescendingRule {
+    fn land(&self, stack: &[usize], landed: usize) -> Landing {
+        knock_out_where(stack, |size| size < landed)
+    }
+}
+
+struct AscendingRule;
+impl StackRule for AscendingRule {
+    fn land(&self, stack: &[usize], landed: usize) -> Landing {
+        knock_out_where(stack, |size| size > landed)
+    }
+}
+
+struct NoMatchingNeighboursRule;
+impl StackRule for NoMatchingNeighboursRule {
+    fn land(&self, stack: &[usize], landed: usize) -> Landing {
+        knock_out_top_if(stack, |top| top == landed)
+    }
+
+    //with too many of one size there aren't enough others to keep them apart
+    fn is_complete(&self, stack: &[usize], level_sizes: &[usize]) -> bool {
+        let most_of_one_size = level_sizes.iter()
+            .map(|size| level_sizes.iter().filter(|other| *other == size).count())
+            .max()
+            .unwrap_or(0);
+        let others = level_sizes.len() - most_of_one_size;
+        stack.len() >= level_sizes.len().min(others * 2 + 1)
+    }
+}
+
+struct AlternatingParityRule;
+impl StackRule for AlternatingParityRule {
+    fn land(&self, stack: &[usize], landed: usize) -> Landing {
+        knock_out_top_if(stack, |top| top % 2 == landed % 2)
+    }
+
+    //the longest alternating stack runs out of whichever parity there's less of
+    fn is_complete(&self, stack: &[usize], level_sizes: &[usize]) -> bool {
+        let odd = level_sizes.iter().filter(|size| *size % 2 == 1).count();
+        let even = level_sizes.len() - odd;
+        let longest = if odd == even { odd * 2 } else { odd.min(even) * 2 + 1 };
+        stack.len() >= longest
+    }
+}
+
+struct HanoiRule;
+impl StackRule for HanoiRule {
+    fn land(&self, stack: &[usize], landed: usize) -> Landing {
+        Landing {
+            knocked_out: Vec::new(),
+            bounced: stack.last().is_some_and(|top| *top < landed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn descending_knocks_off_every_smaller_fish() {
+        let landing = StackRules::Descending.rule().land(&[8, 3, 5, 2], 4);
+        assert_eq!(landing, Landing { knocked_out: vec![1, 3], bounced: false });
+    }
+
+    #[test]
+    fn ascending_knocks_off_every_bigger_fish() {
+        let landing = StackRules::Ascending.rule().land(&[1, 6, 3, 7], 4);
+        assert_eq!(landing, Landing { knocked_out: vec![1, 3], bounced: false });
+    }
+
+    #[test]
+    fn matching_neighbours_only_checks_the_top() {
+        let rule = StackRules::NoMatchingNeighbours.rule();
+        assert_eq!(rule.land(&[4, 2], 4), Landing::default());
+        assert_eq!(rule.land(&[4, 2], 2).knocked_out, vec![1]);
+    }
+
+    #[test]
+    fn parity_completes_when_no_longer_stack_is_possible() {
+        let rule = StackRules::AlternatingParity.rule();
+        assert_eq!(rule.land(&[3, 6], 8).knocked_out, vec![1]);
+        assert!(rule.is_complete(&[3, 6, 9], &[3, 6, 9]));
+        assert!(rule.is_complete(&[3, 6, 9], &[3, 6, 9, 1, 5]));
+        assert!(!rule.is_complete(&[2], &[1, 2, 4]));
+    }
+
+    #[test]
+    fn hanoi_bounces_a_bigger_fish_off_the_top() {
+        let rule = StackRules::Hanoi.rule();
+        assert_eq!(rule.land(&[9, 4], 6), Landing { knocked_out: Vec::new(), bounced: true });
+        assert_eq!(rule.land(&[9, 4], 2), Landing::default());
+        assert_eq!(rule.land(&[], 10), Landing::default());
+    }
+
+    fn land_on(stack: &mut Vec<usize>, rule: &dyn StackRule, landed: usize) -> bool {
+        let landing = rule.land(stack, landed);
+        if !landing.bounced {
+            let mut index = 0;
+            stack.retain(|_| {
+                index += 1;
+                !landing.knocked_out.contains(&(index - 1))
+            });
+            stack.push(landed);
+        }
+        !landing.bounced
+    }
+
+    #[test]
+    fn lone_hanoi_stack_completes_after_a_bounce() {
+        let rule = StackRules::Hanoi.rule();
+        let level_sizes = [2, 5, 9];
+        let mut stack = Vec::new();
+        assert!(land_on(&mut stack, rule, 9));
+        assert!(land_on(&mut stack, rule, 2));
+        //the 5 bounces back into the water, and the 2 has to topple off before it fits
+        assert!(!land_on(&mut stack, rule, 5));
+        assert_eq!(stack, vec![9, 2]);
+        assert!(!rule.is_complete(&stack, &level_sizes));
+        stack.pop();
+        assert!(land_on(&mut stack, rule, 5));
+        assert!(land_on(&mut stack, rule, 2));
+        assert_eq!(stack, vec![9, 5, 2]);
+        assert!(rule.is_complete(&stack, &level_sizes));
+    }
+}
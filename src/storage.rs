@@ -0,0 +1,69 @@
+//! Small key/value persistence for things like high scores and settings.
+//! Native builds write one RON file per key into the user's data directory,
+//! wasm builds use the browser's `localStorage`.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+const APP_DIR_NAME: &str = "hook_line_stacker";
+
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let contents = read_string(key)?;
+    match ron::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            println!("failed to parse saved {}: {}", key, err);
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => write_string(key, &contents),
+        Err(err) => println!("failed to serialize {}: {}", key, err),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn file_path(key: &str) -> Option<std::path::PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_DIR_NAME).join(format!("{}.ron", key)))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_string(key: &str) -> Option<String> {
+    std::fs::read_to_string(file_path(key)?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_string(key: &str, contents: &str) {
+    let Some(path) = file_path(key) else {
+        println!("no data directory to save {} to", key);
+        return;
+    };
+    let result = path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, contents));
+    if let Err(err) = result {
+        println!("failed to save {} to {}: {}", key, path.display(), err);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_string(key: &str) -> Option<String> {
+    local_storage()?.get_item(&format!("{}.{}", APP_DIR_NAME, key)).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_string(key: &str, contents: &str) {
+    let saved = local_storage()
+        .map(|storage| storage.set_item(&format!("{}.{}", APP_DIR_NAME, key), contents).is_ok())
+        .unwrap_or(false);
+    if !saved {
+        println!("failed to save {} to local storage", key);
+    }
+}
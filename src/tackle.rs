@@ -0,0 +1,175 @@
+use bevy::{prelude::*, sprite::Anchor};
+use serde::Deserialize;
+use crate::{
+    controls::{Action, Bindings},
+    core::{FontHandles, GameState, ASSETS_LOADED},
+    hook::{Hook, WaitingToBeCast},
+    settings::Settings,
+    species::FishSpecies
+};
+
+pub struct TacklePlugin;
+impl Plugin for TacklePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(Tackle::default())
+        .add_systems(ASSETS_LOADED, add_tackle_label)
+        .add_systems(Update, choose_tackle.run_if(in_state(GameState::Playing)))
+        .add_systems(PostUpdate, update_tackle_label
+            .run_if(resource_changed::<Tackle>().or_else(resource_changed::<Settings>())));
+    }
+}
+
+/// What's on the hook, decides which species will bite. See `FishSpecies::baits`.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Bait {
+    /// Every fish will take a worm.
+    #[default]
+    Worm,
+    /// Too small to interest the big fish.
+    Cricket,
+    /// Only the big fish go for it.
+    Minnow,
+}
+
+impl Bait {
+    pub const ALL: [Bait; 3] = [Bait::Worm, Bait::Cricket, Bait::Minnow];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Bait::Worm => "WORM",
+            Bait::Cricket => "CRICKET",
+            Bait::Minnow => "MINNOW",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HookKind {
+    #[default]
+    Standard,
+    /// Sinks faster, for getting down to the deep lanes quickly.
+    Heavy,
+    /// Fish bite from further away.
+    Wide,
+}
+
+impl HookKind {
+    pub const ALL: [HookKind; 3] = [HookKind::Standard, HookKind::Heavy, HookKind::Wide];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HookKind::Standard => "STANDARD",
+            HookKind::Heavy => "HEAVY",
+            HookKind::Wide => "WIDE",
+        }
+    }
+
+    /// Multiplies `Hook::move_speed` while moving the hook down.
+    pub fn sink_speed_multiplier(&self, hooks: &HookTuning) -> f32 {
+        match self {
+            HookKind::Heavy => hooks.heavy_sink_speed_multiplier,
+            _ => 1.0,
+        }
+    }
+
+    /// Added to `FishMouth::mouth_size` when checking for a bite.
+    pub fn bite_radius_bonus(&self, hooks: &HookTuning) -> f32 {
+        match self {
+            HookKind::Wide => hooks.wide_bite_radius_bonus,
+            _ => 0.0,
+        }
+    }
+}
+
+/// What the special hooks do, from the tuning file.
+#[derive(Deserialize, Clone, Debug)]
+pub struct HookTuning {
+    pub heavy_sink_speed_multiplier: f32,
+    pub wide_bite_radius_bonus: f32,
+}
+
+impl HookTuning {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.heavy_sink_speed_multiplier <= 0.0 || self.wide_bite_radius_bonus < 0.0 {
+            errors.push(format!("hooks needs a positive heavy_sink_speed_multiplier and wide_bite_radius_bonus, got {:?}", self));
+        }
+        errors
+    }
+}
+
+/// The bait and hook for the next cast, kept between levels.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Tackle {
+    pub bait: Bait,
+    pub hook: HookKind,
+}
+
+impl Tackle {
+    pub fn will_bite(&self, species: &FishSpecies) -> bool {
+        species.baits.contains(&self.bait)
+    }
+}
+
+#[derive(Component)]
+struct TackleLabel;
+
+fn next_in<T: Copy + PartialEq>(all: &[T], current: T) -> T {
+    let index = all.iter().position(|item| *item == current).unwrap_or(0);
+    all[(index + 1) % all.len()]
+}
+
+//the tackle can only be swapped while the hook is out of the water
+fn choose_tackle(
+    actions: Res<Input<Action>>,
+    hook_query: Query<(), (With<Hook>, With<WaitingToBeCast>)>,
+    mut tackle: ResMut<Tackle>
+) {
+    if hook_query.is_empty() {
+        return;
+    }
+    if actions.just_pressed(Action::SwapBait) {
+        tackle.bait = next_in(&Bait::ALL, tackle.bait);
+    }
+    if actions.just_pressed(Action::SwapHook) {
+        tackle.hook = next_in(&HookKind::ALL, tackle.hook);
+    }
+}
+
+fn tackle_label_text(tackle: &Tackle, bindings: &Bindings) -> String {
+    format!("{} BAIT: {}\n{} HOOK: {}",
+        bindings.describe_keys(Action::SwapBait), tackle.bait.label(),
+        bindings.describe_keys(Action::SwapHook), tackle.hook.label())
+}
+
+fn add_tackle_label(
+    fonts: Res<FontHandles>,
+    tackle: Res<Tackle>,
+    settings: Res<Settings>,
+    mut commands: Commands
+) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(tackle_label_text(&tackle, &settings.bindings), TextStyle {
+                font: fonts.timer_font_handle.as_ref().expect("Fonts should be loaded").clone(),
+                font_size: 60.0,
+                color: Color::BLACK,
+            }),
+            text_anchor: Anchor::BottomLeft,
+            transform: Transform::from_translation(Vec3::new(-1320.0, -990.0, 50.0)),
+            ..default()
+        },
+        TackleLabel
+    ));
+}
+
+fn update_tackle_label(
+    tackle: Res<Tackle>,
+    settings: Res<Settings>,
+    mut label_query: Query<&mut Text, With<TackleLabel>>
+) {
+    for mut text in &mut label_query {
+        text.sections[0].value = tackle_label_text(&tackle, &settings.bindings);
+    }
+}
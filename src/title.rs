@@ -0,0 +1,560 @@
+use bevy::{prelude::*, app::AppExit, ecs::system::SystemParam, sprite::Anchor};
+use crate::{
+    controls::{describe_input, Action},
+    core::*,
+    constants::*,
+    high_scores::*,
+    menu::*,
+    pointer::Pointer,
+    rng::SeedConfig,
+    settings::Settings
+};
+
+pub struct TitlePlugin;
+impl Plugin for TitlePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(TitlePage::default())
+        .insert_resource(SeedEntryText::default())
+        .insert_resource(Rebinding::default())
+        .add_systems(OnEnter(GameState::Title), open_main_page)
+        .add_systems(OnExit(GameState::Title), despawn_title_menu)
+        .add_systems(Update, (
+            build_title_page.run_if(resource_changed::<TitlePage>()),
+            handle_title_menu_chosen,
+            handle_title_menu_back,
+            update_title_cursor,
+            type_seed,
+            capture_binding,
+        ).chain().run_if(in_state(GameState::Title)));
+    }
+}
+
+/// Which list the title menu is currently showing.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TitlePage {
+    #[default]
+    Main,
+    ModeSelect,
+    SeedEntry,
+    HighScores,
+    Settings,
+    KeyboardControls,
+    GamepadControls,
+}
+
+/// Digits typed on the seed entry page, empty means a random seed.
+#[derive(Resource, Default)]
+pub struct SeedEntryText(pub String);
+
+/// Progress of rebinding an action on the controls pages.
+#[derive(Resource, Default)]
+pub struct Rebinding {
+    /// The menu is taken away until the next key or button press, which gets bound to this.
+    pub waiting_for: Option<Action>,
+    /// Kept selected when the page is rebuilt.
+    pub last_action: Option<Action>,
+    /// What happened with the last binding, shown under the menu.
+    pub notice: String,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum TitleMenuOption {
+    Play,
+    ModeSelect,
+    Settings,
+    HighScores,
+    Quit,
+    Mode(GameMode),
+    Seed,
+    ConfirmSeed,
+    RandomSeed,
+    KeyboardControls,
+    GamepadControls,
+    Rebind(Action),
+    ResetControls,
+    Back,
+}
+
+#[derive(Component)]
+pub struct TitleMenu;
+
+#[derive(Component)]
+pub struct TitleCursor;
+
+#[derive(Component)]
+pub struct SeedEntryDisplay;
+
+/// Everything a title page reads to fill in its content.
+#[derive(SystemParam)]
+struct TitlePageContent<'w> {
+    mode: Res<'w, GameMode>,
+    high_scores: Res<'w, HighScores>,
+    seed_config: Res<'w, SeedConfig>,
+    seed_text: Res<'w, SeedEntryText>,
+    settings: Res<'w, Settings>,
+    rebinding: Res<'w, Rebinding>,
+}
+
+#[derive(SystemParam)]
+struct SeedEntry<'w> {
+    config: ResMut<'w, SeedConfig>,
+    text: ResMut<'w, SeedEntryText>,
+}
+
+#[derive(SystemParam)]
+struct ControlsEntry<'w> {
+    settings: ResMut<'w, Settings>,
+    rebinding: ResMut<'w, Rebinding>,
+}
+
+const TITLE_ITEMS_TOP_Y: f32 = 150.0;
+const TITLE_ITEMS_SPACING: f32 = 150.0;
+const TITLE_CURSOR_X: f32 = -480.0;
+//the controls pages list every action, so they're packed tighter
+const CONTROLS_ITEMS_TOP_Y: f32 = 250.0;
+const CONTROLS_ITEMS_SPACING: f32 = 100.0;
+const CONTROLS_CURSOR_X: f32 = -1100.0;
+const HIGH_SCORE_ROWS_TOP_Y: f32 = 250.0;
+const HIGH_SCORE_ROWS_SPACING: f32 = 75.0;
+
+fn title_page_items_top_y(page: TitlePage) -> f32 {
+    match page {
+        //leave room for the table above the back button
+        TitlePage::HighScores => -650.0,
+        TitlePage::SeedEntry => -100.0,
+        TitlePage::KeyboardControls | TitlePage::GamepadControls => CONTROLS_ITEMS_TOP_Y,
+        _ => TITLE_ITEMS_TOP_Y,
+    }
+}
+
+fn title_page_items_spacing(page: TitlePage) -> f32 {
+    match page {
+        TitlePage::KeyboardControls | TitlePage::GamepadControls => CONTROLS_ITEMS_SPACING,
+        _ => TITLE_ITEMS_SPACING,
+    }
+}
+
+fn title_page_cursor_x(page: TitlePage) -> f32 {
+    match page {
+        TitlePage::KeyboardControls | TitlePage::GamepadControls => CONTROLS_CURSOR_X,
+        _ => TITLE_CURSOR_X,
+    }
+}
+
+fn title_page_options(page: TitlePage, content: &TitlePageContent) -> Vec<(TitleMenuOption, String, bool)> {
+    let options: Vec<(TitleMenuOption, &str, bool)> = match page {
+        TitlePage::Main => vec![
+            (TitleMenuOption::Play, "PLAY", true),
+            (TitleMenuOption::ModeSelect, "MODE SELECT", true),
+            (TitleMenuOption::Settings, "SETTINGS", true),
+            (TitleMenuOption::HighScores, "HIGH SCORES", true),
+            (TitleMenuOption::Quit, "QUIT", true),
+        ],
+        TitlePage::ModeSelect => {
+            let seed_label = match content.seed_config.fixed_seed {
+                Some(seed) => format!("SEED: {}", seed),
+                None => "SEED: RANDOM".to_string(),
+            };
+            return GameMode::ALL.iter()
+                .map(|mode| (TitleMenuOption::Mode(*mode), mode.label().to_string(), true))
+                .chain([
+                    (TitleMenuOption::Seed, seed_label, true),
+                    (TitleMenuOption::Back, "BACK".to_string(), true),
+                ])
+                .collect();
+        },
+        TitlePage::SeedEntry => vec![
+            (TitleMenuOption::ConfirmSeed, "OK", true),
+            (TitleMenuOption::RandomSeed, "RANDOM", true),
+            (TitleMenuOption::Back, "BACK", true),
+        ],
+        TitlePage::HighScores => vec![
+            (TitleMenuOption::Back, "BACK", true),
+        ],
+        TitlePage::Settings => vec![
+            (TitleMenuOption::KeyboardControls, "KEYBOARD CONTROLS", true),
+            (TitleMenuOption::GamepadControls, "GAMEPAD CONTROLS", true),
+            (TitleMenuOption::Back, "BACK", true),
+        ],
+        TitlePage::KeyboardControls | TitlePage::GamepadControls => {
+            let is_keyboard = page == TitlePage::KeyboardControls;
+            let bindings = &content.settings.bindings;
+            return Action::ALL.iter()
+                .map(|action| {
+                    let bound_to = match (content.rebinding.waiting_for == Some(*action), is_keyboard) {
+                        (true, true) => "PRESS A KEY".to_string(),
+                        (true, false) => "PRESS A BUTTON".to_string(),
+                        (false, true) => bindings.describe_keys(*action),
+                        (false, false) => bindings.describe_buttons(*action),
+                    };
+                    (TitleMenuOption::Rebind(*action), format!("{}   {}", action.label(), bound_to), true)
+                })
+                .chain([
+                    (TitleMenuOption::ResetControls, "RESET TO DEFAULTS".to_string(), true),
+                    (TitleMenuOption::Back, "BACK".to_string(), true),
+                ])
+                .collect();
+        },
+    };
+    options.into_iter()
+        .map(|(option, label, enabled)| (option, label.to_string(), enabled))
+        .collect()
+}
+
+fn open_main_page(
+    mut page: ResMut<TitlePage>
+) {
+    //always mark as changed, so the menu gets rebuilt even if we were already on the main page
+    *page = TitlePage::Main;
+}
+
+fn build_title_page(
+    page: Res<TitlePage>,
+    content: TitlePageContent,
+    menu_query: Query<Entity, With<TitleMenu>>,
+    fonts: Res<FontHandles>,
+    images: Res<ImageHandles>,
+    mut commands: Commands
+) {
+    for entity in &menu_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    let font = fonts.timer_font_handle.as_ref().expect("Fonts should be loaded");
+    let options = title_page_options(*page, &content);
+    let selected = options.iter()
+        .position(|(option, _, _)| match option {
+            TitleMenuOption::Mode(mode) => *mode == *content.mode,
+            TitleMenuOption::Rebind(action) => Some(*action) == content.rebinding.last_action,
+            _ => false,
+        })
+        .unwrap_or(0);
+    let mut title_menu = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.35),
+                custom_size: Some(BACKGROUND_SIZE),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 200.0)),
+            ..default()
+        },
+        TitleMenu
+    ));
+    //while waiting for a new binding, the keys that would move around the menu get bound instead
+    if content.rebinding.waiting_for.is_none() {
+        title_menu.insert(Menu { selected, item_count: options.len() });
+    }
+    title_menu.with_children(|parent| {
+        let heading = match *page {
+            TitlePage::Main => "GO FISH",
+            TitlePage::ModeSelect => "MODE SELECT",
+            TitlePage::SeedEntry => "ENTER SEED",
+            TitlePage::HighScores => "HIGH SCORES",
+            TitlePage::Settings => "SETTINGS",
+            TitlePage::KeyboardControls => "KEYBOARD",
+            TitlePage::GamepadControls => "GAMEPAD",
+        };
+        parent.spawn(Text2dBundle {
+            text: Text::from_section(heading, TextStyle {
+                font: font.clone(),
+                font_size: 260.0,
+                color: Color::WHITE,
+            }),
+            text_anchor: Anchor::Center,
+            transform: Transform::from_translation(Vec3::new(0.0, 450.0, 1.0)),
+            ..default()
+        });
+        if *page == TitlePage::HighScores {
+            spawn_high_score_rows(parent, font, &content.high_scores, *content.mode);
+        }
+        if *page == TitlePage::SeedEntry {
+            parent.spawn((
+                Text2dBundle {
+                    text: Text::from_section(seed_entry_label(&content.seed_text.0), TextStyle {
+                        font: font.clone(),
+                        font_size: 160.0,
+                        color: Color::WHITE,
+                    }),
+                    text_anchor: Anchor::Center,
+                    transform: Transform::from_translation(Vec3::new(0.0, 150.0, 1.0)),
+                    ..default()
+                },
+                SeedEntryDisplay
+            ));
+        }
+        if matches!(*page, TitlePage::KeyboardControls | TitlePage::GamepadControls) {
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(content.rebinding.notice.clone(), TextStyle {
+                    font: font.clone(),
+                    font_size: 70.0,
+                    color: Color::WHITE,
+                }),
+                text_anchor: Anchor::Center,
+                transform: Transform::from_translation(Vec3::new(0.0, -880.0, 1.0)),
+                ..default()
+            });
+        }
+        let items_top_y = title_page_items_top_y(*page);
+        let items_spacing = title_page_items_spacing(*page);
+        for (index, (option, label, enabled)) in options.iter().enumerate() {
+            spawn_menu_item(
+                parent,
+                font,
+                label.as_str(),
+                index,
+                *enabled,
+                Vec3::new(0.0, items_top_y - items_spacing * index as f32, 1.0),
+                *option
+            );
+        }
+        parent.spawn((
+            SpriteSheetBundle {
+                texture_atlas: images.misc_atlas_handle.as_ref().expect("Images should be loaded").clone(),
+                sprite: TextureAtlasSprite::new(6),
+                transform: Transform {
+                    translation: Vec3::new(title_page_cursor_x(*page), items_top_y - items_spacing * selected as f32, 1.0),
+                    scale: Vec3::new(2.0, 2.0, 1.0),
+                    ..default()
+                },
+                ..default()
+            },
+            TitleCursor
+        ));
+    });
+}
+
+fn spawn_high_score_rows(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    high_scores: &HighScores,
+    mode: GameMode
+) {
+    let text_style = TextStyle {
+        font: font.clone(),
+        font_size: 70.0,
+        color: Color::WHITE,
+    };
+    let mut rows: Vec<String> = high_scores.for_mode(mode)
+        .enumerate()
+        .map(|(index, entry)| {
+            format!("{:>2}.  {:>7.1} s   SEED {}", index + 1, entry.time_s, entry.seed)
+        })
+        .collect();
+    if rows.is_empty() {
+        rows.push("NO WINS YET".to_string());
+    }
+    rows.insert(0, mode.label().to_string());
+    for (index, row) in rows.into_iter().enumerate() {
+        parent.spawn(Text2dBundle {
+            text: Text::from_section(row, text_style.clone()),
+            text_anchor: Anchor::Center,
+            transform: Transform::from_translation(
+                Vec3::new(0.0, HIGH_SCORE_ROWS_TOP_Y - HIGH_SCORE_ROWS_SPACING * index as f32, 1.0)),
+            ..default()
+        });
+    }
+}
+
+fn handle_title_menu_chosen(
+    mut on_chosen: EventReader<MenuItemChosen>,
+    option_query: Query<&TitleMenuOption>,
+    mut page: ResMut<TitlePage>,
+    mut mode: ResMut<GameMode>,
+    (mut seed_entry, mut controls): (SeedEntry, ControlsEntry),
+    mut next_state: ResMut<NextState<GameState>>,
+    mut on_exit: EventWriter<AppExit>
+) {
+    for event in on_chosen.iter() {
+        if let Ok(option) = option_query.get(event.item_entity) {
+            match option {
+                TitleMenuOption::Play => next_state.set(GameState::Playing),
+                TitleMenuOption::ModeSelect => *page = TitlePage::ModeSelect,
+                TitleMenuOption::Settings => *page = TitlePage::Settings,
+                TitleMenuOption::HighScores => *page = TitlePage::HighScores,
+                TitleMenuOption::Quit => on_exit.send(AppExit),
+                TitleMenuOption::Mode(chosen_mode) => {
+                    *mode = *chosen_mode;
+                    *page = TitlePage::Main;
+                },
+                TitleMenuOption::Seed => {
+                    seed_entry.text.0 = seed_entry.config.fixed_seed.map_or(String::new(), |seed| seed.to_string());
+                    *page = TitlePage::SeedEntry;
+                },
+                TitleMenuOption::ConfirmSeed => {
+                    seed_entry.config.fixed_seed = seed_entry.text.0.parse::<u64>().ok();
+                    *page = TitlePage::ModeSelect;
+                },
+                TitleMenuOption::RandomSeed => {
+                    seed_entry.config.fixed_seed = None;
+                    *page = TitlePage::ModeSelect;
+                },
+                TitleMenuOption::KeyboardControls => {
+                    controls.rebinding.notice.clear();
+                    *page = TitlePage::KeyboardControls;
+                },
+                TitleMenuOption::GamepadControls => {
+                    controls.rebinding.notice.clear();
+                    *page = TitlePage::GamepadControls;
+                },
+                TitleMenuOption::Rebind(action) => {
+                    controls.rebinding.waiting_for = Some(*action);
+                    controls.rebinding.last_action = Some(*action);
+                    let bindings = &controls.settings.bindings;
+                    let cancel_with =
+                        if *page == TitlePage::KeyboardControls {
+                            bindings.describe_keys(Action::Pause)
+                        } else {
+                            bindings.describe_buttons(Action::Pause)
+                        };
+                    controls.rebinding.notice = format!("{} TO CANCEL", cancel_with);
+                    page.set_changed();
+                },
+                TitleMenuOption::ResetControls => {
+                    controls.settings.bindings = default();
+                    controls.settings.save();
+                    controls.rebinding.last_action = None;
+                    controls.rebinding.notice = "CONTROLS RESET".to_string();
+                    page.set_changed();
+                },
+                TitleMenuOption::Back => *page = previous_page(*page),
+            }
+        }
+    }
+}
+
+fn handle_title_menu_back(
+    mut on_back: EventReader<MenuBack>,
+    mut page: ResMut<TitlePage>
+) {
+    if !on_back.is_empty() {
+        on_back.clear();
+        if *page != TitlePage::Main {
+            *page = previous_page(*page);
+        }
+    }
+}
+
+fn previous_page(page: TitlePage) -> TitlePage {
+    match page {
+        TitlePage::SeedEntry => TitlePage::ModeSelect,
+        TitlePage::KeyboardControls | TitlePage::GamepadControls => TitlePage::Settings,
+        _ => TitlePage::Main,
+    }
+}
+
+fn seed_entry_label(seed_text: &str) -> String {
+    if seed_text.is_empty() {
+        "RANDOM".to_string()
+    } else {
+        seed_text.to_string()
+    }
+}
+
+fn type_seed(
+    page: Res<TitlePage>,
+    mut on_character: EventReader<ReceivedCharacter>,
+    input: Res<Input<KeyCode>>,
+    mut seed_text: ResMut<SeedEntryText>,
+    mut display_query: Query<&mut Text, With<SeedEntryDisplay>>
+) {
+    if *page != TitlePage::SeedEntry {
+        on_character.clear();
+        return;
+    }
+    let mut changed = false;
+    for event in on_character.iter() {
+        if event.char.is_ascii_digit() {
+            let mut new_text = seed_text.0.clone();
+            new_text.push(event.char);
+            //anything that no longer fits in a u64 is ignored
+            if new_text.parse::<u64>().is_ok() {
+                seed_text.0 = new_text;
+                changed = true;
+            }
+        }
+    }
+    if input.just_pressed(KeyCode::Back) {
+        changed |= seed_text.0.pop().is_some();
+    }
+    if changed {
+        if let Ok(mut text) = display_query.get_single_mut() {
+            text.sections[0].value = seed_entry_label(&seed_text.0);
+        }
+    }
+}
+
+//the next key or button pressed gets bound, unless it's bound to pause or it's a click,
+//which cancel. moving pause off escape frees escape up for something else
+fn capture_binding(
+    mut page: ResMut<TitlePage>,
+    actions: Res<Input<Action>>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    pointer: Res<Pointer>,
+    mut controls: ControlsEntry
+) {
+    let Some(action) = controls.rebinding.waiting_for else {
+        return;
+    };
+    //the press that picked the action to rebind is still down this frame
+    if page.is_changed() {
+        return;
+    }
+    let is_keyboard = *page == TitlePage::KeyboardControls;
+    if actions.just_pressed(Action::Pause) || pointer.just_pressed {
+        controls.rebinding.waiting_for = None;
+        controls.rebinding.notice.clear();
+        page.set_changed();
+        return;
+    }
+    let bindings = &mut controls.settings.bindings;
+    let (input_name, conflict) =
+        if is_keyboard {
+            let Some(key) = keys.get_just_pressed().next() else {
+                return;
+            };
+            (describe_input(key), bindings.bind_key(action, *key))
+        } else {
+            let Some(button) = buttons.get_just_pressed().next() else {
+                return;
+            };
+            (describe_input(button.button_type), bindings.bind_button(action, button.button_type))
+        };
+    controls.rebinding.notice = match conflict {
+        Some(conflict) => {
+            let now_bound_to =
+                if is_keyboard {
+                    bindings.describe_keys(conflict)
+                } else {
+                    bindings.describe_buttons(conflict)
+                };
+            format!("{} WAS ON {}, IT'S NOW {}", input_name, conflict.label(), now_bound_to)
+        },
+        None => String::new(),
+    };
+    controls.settings.save();
+    controls.rebinding.waiting_for = None;
+    page.set_changed();
+}
+
+fn update_title_cursor(
+    page: Res<TitlePage>,
+    menu_query: Query<&Menu, With<TitleMenu>>,
+    mut cursor_query: Query<&mut Transform, With<TitleCursor>>
+) {
+    if let Ok(menu) = menu_query.get_single() {
+        if let Ok(mut cursor_pos) = cursor_query.get_single_mut() {
+            cursor_pos.translation.y = title_page_items_top_y(*page) - title_page_items_spacing(*page) * menu.selected as f32;
+        }
+    }
+}
+
+fn despawn_title_menu(
+    menu_query: Query<Entity, With<TitleMenu>>,
+    mut commands: Commands
+) {
+    for entity in &menu_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::{
+    asset::{AssetLoader, ChangeWatcher, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture
+};
+use serde::Deserialize;
+use crate::{catch_grade::CatchWindows, catch_stack::StackWobble, chase::ChaseTuning, fish::EscapeTuning, hook::ReelTuning, score::ScoreTuning, tackle::HookTuning, level::{CurrentLevel, LevelDefinition}, species::FishSpeciesList};
+
+pub struct TuningPlugin;
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        //the copy built into the binary means there's always a valid tuning,
+        //even before the asset loads or on platforms that can't read it
+        let tuning = Tuning::parse(DEFAULT_TUNING.as_bytes())
+            .expect("built in tuning should be valid");
+        app
+        .add_asset::<Tuning>()
+        .add_asset_loader(TuningLoader)
+        .insert_resource(tuning)
+        .insert_resource(TuningHandle::default())
+        .add_systems(PreStartup, load_tuning)
+        .add_systems(PreUpdate, apply_tuning);
+    }
+}
+
+const TUNING_PATH: &str = "game.tuning.ron";
+const DEFAULT_TUNING: &str = include_str!("../assets/game.tuning.ron");
+
+/// How often changes to the tuning file are picked up while the game runs.
+pub fn tuning_change_watcher() -> Option<ChangeWatcher> {
+    ChangeWatcher::with_delay(std::time::Duration::from_millis(200))
+}
+
+/// Gameplay numbers loaded from `assets/game.tuning.ron`.
+#[derive(Resource, Deserialize, TypeUuid, TypePath, Clone, Debug)]
+#[uuid = "6b0d9f52-0d4e-4c4f-9a53-3a6f2f8c1e27"]
+pub struct Tuning {
+    pub gravity: f32,
+    pub water_drag_x: f32,
+    pub water_drag_y: f32,
+    pub catch_windows: CatchWindows,
+    pub stack_wobble: StackWobble,
+    pub chase: ChaseTuning,
+    pub reeling: ReelTuning,
+    pub escape: EscapeTuning,
+    pub scoring: ScoreTuning,
+    pub hooks: HookTuning,
+    pub classic_level: LevelDefinition,
+    pub campaign_levels: Vec<LevelDefinition>,
+}
+
+impl Tuning {
+    fn parse(bytes: &[u8]) -> Result<Tuning, String> {
+        let tuning: Tuning = ron::de::from_bytes(bytes).map_err(|err| err.to_string())?;
+        let errors = tuning.validate();
+        if errors.is_empty() {
+            Ok(tuning)
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    /// Everything wrong with the values, one message per problem.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.gravity <= 0.0 {
+            errors.push(format!("gravity must be positive, got {}", self.gravity));
+        }
+        if self.water_drag_x < 0.0 || self.water_drag_y < 0.0 {
+            errors.push(format!("water drag can't be negative, got ({}, {})", self.water_drag_x, self.water_drag_y));
+        }
+        errors.extend(self.catch_windows.validate());
+        errors.extend(self.stack_wobble.validate());
+        errors.extend(self.chase.validate());
+        errors.extend(self.reeling.validate());
+        errors.extend(self.escape.validate());
+        errors.extend(self.scoring.validate());
+        errors.extend(self.hooks.validate());
+        if self.campaign_levels.is_empty() {
+            errors.push("campaign_levels needs at least one level".to_string());
+        }
+        let levels = std::iter::once(("classic_level".to_string(), &self.classic_level))
+            .chain(self.campaign_levels.iter()
+                .enumerate()
+                .map(|(index, level)| (format!("campaign_levels[{}]", index), level)));
+        for (name, level) in levels {
+            for error in level.validate() {
+                errors.push(format!("{}: {}", name, error));
+            }
+            if level.critical_time > self.catch_windows.great_s {
+                errors.push(format!("{}: critical_time can't be longer than catch_windows.great_s", name));
+            }
+        }
+        errors
+    }
+}
+
+#[derive(Resource, Default)]
+struct TuningHandle(Handle<Tuning>);
+
+struct TuningLoader;
+impl AssetLoader for TuningLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            //a bad edit is rejected here, so the game keeps running with the last good values
+            let tuning = Tuning::parse(bytes).map_err(|err| {
+                println!("invalid tuning in {}:\n{}", load_context.path().display(), err);
+                bevy::asset::Error::msg(err)
+            })?;
+            load_context.set_default_asset(LoadedAsset::new(tuning));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.ron"]
+    }
+}
+
+fn load_tuning(
+    mut tuning_handle: ResMut<TuningHandle>,
+    asset_server: Res<AssetServer>
+) {
+    tuning_handle.0 = asset_server.load(TUNING_PATH);
+}
+
+fn apply_tuning(
+    mut on_tuning_event: EventReader<AssetEvent<Tuning>>,
+    tuning_handle: Res<TuningHandle>,
+    tuning_assets: Res<Assets<Tuning>>,
+    species: Res<FishSpeciesList>,
+    mut tuning: ResMut<Tuning>,
+    mut current_level: ResMut<CurrentLevel>
+) {
+    for event in on_tuning_event.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            if *handle != tuning_handle.0 {
+                continue;
+            }
+            let Some(loaded) = tuning_assets.get(handle) else {
+                continue;
+            };
+            let errors = species.check_levels(loaded);
+            if !errors.is_empty() {
+                println!("ignoring tuning from {}:\n{}", TUNING_PATH, errors.join("\n"));
+                continue;
+            }
+            println!("loaded tuning from {}", TUNING_PATH);
+            *tuning = loaded.clone();
+            current_level.refresh(&tuning);
+        }
+    }
+}
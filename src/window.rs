@@ -1,5 +1,5 @@
 use bevy::{prelude::*, window::{WindowResized, PrimaryWindow, WindowResolution }};
-use crate::constants::*;
+use crate::{constants::*, tuning::tuning_change_watcher};
 
 pub struct HSLWindowPlugin;
 impl Plugin for HSLWindowPlugin {
@@ -15,6 +15,10 @@ impl Plugin for HSLWindowPlugin {
                     ..default()
                 }),
                 ..default()
+            })
+            .set(AssetPlugin {
+                watch_for_changes: tuning_change_watcher(),
+                ..default()
             }))
         .add_systems(PostUpdate, window_resize);
     }